@@ -0,0 +1,66 @@
+//! The interface of the `accumulator` contract.
+//!
+//! Contracts calling the `accumulator` go through the call builders generated for
+//! `Accumulating` rather than building calls from the raw selectors, so the argument
+//! and return types are checked at compile time.
+#![cfg_attr(not(feature = "std"), no_std)]
+
+use selectors::{CHANGE_SELECTOR, DEC_SELECTOR, GET_SELECTOR, RESET_SELECTOR};
+
+const _: () = assert!(u32::from_be_bytes(CHANGE_SELECTOR) == 0xC0DECAFE);
+const _: () = assert!(u32::from_be_bytes(DEC_SELECTOR) == 0xC0DECADD);
+const _: () = assert!(u32::from_be_bytes(GET_SELECTOR) == 0xC0DECAF1);
+const _: () = assert!(u32::from_be_bytes(RESET_SELECTOR) == 0xC0DECAF2);
+
+/// The type of the accumulated value and of the amounts it is changed by.
+///
+/// # Migration
+///
+/// This used to be `i32`. The message selectors are unchanged, but the encoding
+/// of the arguments and return values is not, so callers have to be updated and
+/// existing `accumulator` contracts have to be redeployed.
+pub type Value = i128;
+
+/// Errors that can occur upon calling the `accumulator` contract.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, scale::Decode, scale::Encode)]
+#[cfg_attr(feature = "std", derive(scale_info::TypeInfo))]
+pub enum AccumulatorError {
+    /// The value would overflow the bounds of a `Value`.
+    Overflow,
+    /// The value would drop below the minimum value.
+    Underflow,
+    /// The caller is not the owner.
+    Unauthorized,
+}
+
+/// Holds a `Value` that can be incremented and decremented.
+///
+/// The messages keep the shared selectors, so they can still be called the way
+/// the `adder` and `subber` are called by the `delegator`.
+#[ink::trait_definition]
+pub trait Accumulating {
+    /// Mutates the value by some amount.
+    ///
+    /// Returns `AccumulatorError::Overflow` if the value would overflow and
+    /// `AccumulatorError::Underflow` if it would drop below the minimum value.
+    #[ink(message, payable, selector = 0xC0DECAFE)]
+    fn inc(&mut self, by: Value) -> Result<(), AccumulatorError>;
+
+    /// Decreases the value by some amount.
+    ///
+    /// Returns `AccumulatorError::Overflow` if the value would overflow and
+    /// `AccumulatorError::Underflow` if it would drop below the minimum value.
+    #[ink(message, payable, selector = 0xC0DECADD)]
+    fn dec(&mut self, by: Value) -> Result<(), AccumulatorError>;
+
+    /// Returns the current value.
+    #[ink(message, selector = 0xC0DECAF1)]
+    fn get(&self) -> Value;
+
+    /// Sets the value back to zero.
+    ///
+    /// Returns `AccumulatorError::Unauthorized` if the caller is not the owner and
+    /// `AccumulatorError::Underflow` if zero is below the minimum value.
+    #[ink(message, selector = 0xC0DECAF2)]
+    fn reset(&mut self) -> Result<(), AccumulatorError>;
+}
@@ -0,0 +1,207 @@
+#![cfg_attr(not(feature = "std"), no_std)]
+
+pub use self::accumulator::{Accumulator, AccumulatorRef};
+pub use accumulating::{Accumulating, AccumulatorError, Value};
+
+#[ink::contract]
+pub mod accumulator {
+    use accumulating::{Accumulating, AccumulatorError, Value};
+
+    /// Holds a simple `Value` that can be incremented and decremented.
+    #[ink(storage)]
+    pub struct Accumulator {
+        /// The accumulated value.
+        value: Value,
+        /// The value the accumulated value may not drop below.
+        min_value: Value,
+        /// The account allowed to reset the value and to hand over the ownership.
+        owner: AccountId,
+    }
+
+    impl Accumulator {
+        /// Initializes the value to the initial value, without a minimum value.
+        #[ink(constructor)]
+        pub fn new(init_value: Value) -> Self {
+            Self::with_min_value(init_value, Value::MIN)
+        }
+
+        /// Initializes the value to the initial value, which may not drop below
+        /// `min_value`, e.g. `0` if the value models a balance.
+        ///
+        /// The initial value is not checked against `min_value`, but it cannot be
+        /// decreased until it is at least `min_value`. The caller becomes the owner.
+        #[ink(constructor)]
+        pub fn with_min_value(init_value: Value, min_value: Value) -> Self {
+            Self {
+                value: init_value,
+                min_value,
+                owner: Self::env().caller(),
+            }
+        }
+
+        /// Returns the owner of the `accumulator`.
+        #[ink(message)]
+        pub fn owner(&self) -> AccountId {
+            self.owner
+        }
+
+        /// Hands the ownership over to `new_owner`, e.g. the `delegator` changing the
+        /// `accumulator`.
+        ///
+        /// Returns `AccumulatorError::Unauthorized` if the caller is not the owner.
+        #[ink(message)]
+        pub fn set_owner(&mut self, new_owner: AccountId) -> Result<(), AccumulatorError> {
+            self.ensure_owner()?;
+            self.owner = new_owner;
+            Ok(())
+        }
+
+        /// Returns `AccumulatorError::Unauthorized` if the caller is not the owner.
+        fn ensure_owner(&self) -> Result<(), AccumulatorError> {
+            if self.env().caller() != self.owner {
+                return Err(AccumulatorError::Unauthorized);
+            }
+            Ok(())
+        }
+
+        /// Returns the value the accumulated value may not drop below.
+        #[ink(message)]
+        pub fn min_value(&self) -> Value {
+            self.min_value
+        }
+
+        /// Sets the value to the result of a checked operation on it.
+        fn set(&mut self, value: Option<Value>) -> Result<(), AccumulatorError> {
+            let value = value.ok_or(AccumulatorError::Overflow)?;
+            if value < self.min_value {
+                return Err(AccumulatorError::Underflow);
+            }
+            self.value = value;
+            Ok(())
+        }
+    }
+
+    impl Accumulating for Accumulator {
+        /// Mutates the internal value by some amount.
+        ///
+        /// Any transferred value is kept by the `accumulator`.
+        #[ink(message)]
+        fn inc(&mut self, by: Value) -> Result<(), AccumulatorError> {
+            self.set(self.value.checked_add(by))
+        }
+
+        /// Decreases the internal value by some amount.
+        ///
+        /// Any transferred value is kept by the `accumulator`.
+        #[ink(message)]
+        fn dec(&mut self, by: Value) -> Result<(), AccumulatorError> {
+            self.set(self.value.checked_sub(by))
+        }
+
+        /// Returns the current value.
+        #[ink(message)]
+        fn get(&self) -> Value {
+            self.value
+        }
+
+        /// Sets the value back to zero, unless zero is below `min_value`.
+        ///
+        /// Can only be called by the owner.
+        #[ink(message)]
+        fn reset(&mut self) -> Result<(), AccumulatorError> {
+            self.ensure_owner()?;
+            self.set(Some(0))
+        }
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+        use ink::reflect::DispatchableMessageInfo;
+        use selectors::{CHANGE_SELECTOR, DEC_SELECTOR, GET_SELECTOR, RESET_SELECTOR};
+
+        /// Returns the selector and name of the message with the given selector.
+        fn message<const ID: u32>() -> ([u8; 4], &'static str)
+        where
+            Accumulator: DispatchableMessageInfo<ID>,
+        {
+            (
+                <Accumulator as DispatchableMessageInfo<ID>>::SELECTOR,
+                <Accumulator as DispatchableMessageInfo<ID>>::LABEL,
+            )
+        }
+
+        #[ink::test]
+        fn min_value_is_enforced() {
+            let mut accumulator = Accumulator::with_min_value(5, 0);
+            assert_eq!(accumulator.min_value(), 0);
+            assert_eq!(accumulator.dec(6), Err(AccumulatorError::Underflow));
+            assert_eq!(accumulator.inc(-6), Err(AccumulatorError::Underflow));
+            assert_eq!(accumulator.get(), 5);
+            assert_eq!(accumulator.dec(5), Ok(()));
+            assert_eq!(accumulator.get(), 0);
+            assert_eq!(Accumulator::new(0).min_value(), Value::MIN);
+        }
+
+        #[ink::test]
+        fn reset_respects_min_value() {
+            let mut accumulator = Accumulator::with_min_value(7, 5);
+            assert_eq!(accumulator.reset(), Err(AccumulatorError::Underflow));
+            assert_eq!(accumulator.get(), 7);
+            let mut accumulator = Accumulator::with_min_value(7, 0);
+            assert_eq!(accumulator.reset(), Ok(()));
+            assert_eq!(accumulator.get(), 0);
+        }
+
+        #[ink::test]
+        fn only_owner_can_reset() {
+            let accounts = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+            let mut accumulator = Accumulator::new(5);
+            assert_eq!(accumulator.owner(), accounts.alice);
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.bob);
+            assert_eq!(accumulator.reset(), Err(AccumulatorError::Unauthorized));
+            assert_eq!(
+                accumulator.set_owner(accounts.bob),
+                Err(AccumulatorError::Unauthorized)
+            );
+            assert_eq!(accumulator.get(), 5);
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.alice);
+            assert_eq!(accumulator.set_owner(accounts.bob), Ok(()));
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.bob);
+            assert_eq!(accumulator.reset(), Ok(()));
+            assert_eq!(accumulator.get(), 0);
+        }
+
+        #[test]
+        fn inc_selector_matches() {
+            assert_eq!(
+                message::<{ u32::from_be_bytes(CHANGE_SELECTOR) }>(),
+                (CHANGE_SELECTOR, "Accumulating::inc")
+            );
+        }
+
+        #[test]
+        fn dec_selector_matches() {
+            assert_eq!(
+                message::<{ u32::from_be_bytes(DEC_SELECTOR) }>(),
+                (DEC_SELECTOR, "Accumulating::dec")
+            );
+        }
+
+        #[test]
+        fn get_selector_matches() {
+            assert_eq!(
+                message::<{ u32::from_be_bytes(GET_SELECTOR) }>(),
+                (GET_SELECTOR, "Accumulating::get")
+            );
+        }
+
+        #[test]
+        fn reset_selector_matches() {
+            assert_eq!(
+                message::<{ u32::from_be_bytes(RESET_SELECTOR) }>(),
+                (RESET_SELECTOR, "Accumulating::reset")
+            );
+        }
+    }
+}
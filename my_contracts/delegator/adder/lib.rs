@@ -1,35 +1,205 @@
 #![cfg_attr(not(feature = "std"), no_std)]
 
+pub use self::adder::{Adder, AdderError, AdderRef};
+
 #[ink::contract]
-mod adder {
-    use ink::env::{
-        call::{build_call, Call, ExecutionInput, Selector},
-        CallFlags, DefaultEnvironment,
-    };
+pub mod adder {
+    use accumulator::{Accumulating, AccumulatorError, AccumulatorRef, Value};
+    use ink::{codegen::TraitCallBuilder, env::call::FromAccountId};
+    use selectors::{CHANGE_SELECTOR, DELEGATED_CHANGE_SELECTOR, DELEGATED_VALUE_KEY};
+
+    const _: () = assert!(u32::from_be_bytes(CHANGE_SELECTOR) == 0xC0DECAFE);
+    const _: () = assert!(u32::from_be_bytes(DELEGATED_CHANGE_SELECTOR) == 0xC0DECAFD);
+
+    /// Errors that can occur upon calling the `adder` contract.
+    #[derive(Debug, Copy, Clone, PartialEq, Eq, scale::Decode, scale::Encode)]
+    #[cfg_attr(feature = "std", derive(scale_info::TypeInfo))]
+    pub enum AdderError {
+        /// The `accumulator` value would overflow the bounds of a `Value`.
+        Overflow,
+        /// The `accumulator` value would drop below its minimum value.
+        Underflow,
+        /// The call to the `accumulator` failed.
+        AccumulatorCallFailed,
+        /// The caller is not the owner.
+        Unauthorized,
+    }
+
     /// Increments the underlying `accumulator` value.
     #[ink(storage)]
     pub struct Adder {
         /// The `accumulator` to store the value.
         acc_contract: AccountId,
+        /// The account allowed to set the `accumulator`.
+        owner: AccountId,
     }
 
     impl Adder {
-        /// Creates a new `adder` from the given `accumulator`.
+        /// Creates a new `adder` from the given `accumulator`, owned by the caller.
         #[ink(constructor)]
         pub fn new(acc_contract: AccountId) -> Self {
-            Self { acc_contract }
-        }
-
-        /// Increases the `accumulator` value by some amount.
-        #[ink(message, selector = 0xC0DECAFE)]
-        pub fn inc(&mut self, by: i32) {
-            let method_selector = [0xC0, 0xDE, 0xCA, 0xFE];
-            let _result = build_call::<<Self as ::ink::env::ContractEnv>::Env>()
-                .call(self.acc_contract)
-                .call_flags(CallFlags::default())
-                .exec_input(ExecutionInput::new(method_selector.into()).push_arg(by))
-                .returns::<()>()
+            Self {
+                acc_contract,
+                owner: Self::env().caller(),
+            }
+        }
+
+        /// Returns the `accumulator` smart contract.
+        #[ink(message)]
+        pub fn accumulator(&self) -> AccountId {
+            self.acc_contract
+        }
+
+        /// Sets the `accumulator` smart contract.
+        ///
+        /// Returns `AdderError::Unauthorized` if the caller is not the owner.
+        #[ink(message)]
+        pub fn set_accumulator(&mut self, addr: AccountId) -> Result<(), AdderError> {
+            self.ensure_owner()?;
+            self.acc_contract = addr;
+            Ok(())
+        }
+
+        /// Returns the owner of the `adder`.
+        #[ink(message)]
+        pub fn owner(&self) -> AccountId {
+            self.owner
+        }
+
+        /// Hands the ownership over to `new_owner`, e.g. the `delegator` migrating the
+        /// `accumulator`.
+        ///
+        /// Returns `AdderError::Unauthorized` if the caller is not the owner.
+        #[ink(message)]
+        pub fn set_owner(&mut self, new_owner: AccountId) -> Result<(), AdderError> {
+            self.ensure_owner()?;
+            self.owner = new_owner;
+            Ok(())
+        }
+
+        /// Returns `AdderError::Unauthorized` if the caller is not the owner.
+        fn ensure_owner(&self) -> Result<(), AdderError> {
+            if self.env().caller() != self.owner {
+                return Err(AdderError::Unauthorized);
+            }
+            Ok(())
+        }
+
+        /// Increases the `accumulator` value by some amount and returns the new value.
+        ///
+        /// Returns `AdderError::Overflow` if the `accumulator` value would overflow and
+        /// `AdderError::Underflow` if it would drop below its minimum value.
+        ///
+        /// Any transferred value is forwarded to the `accumulator`. If a call to the
+        /// `accumulator` fails, `AdderError::AccumulatorCallFailed` is returned and the
+        /// transfer is reverted.
+        #[ink(message, payable, selector = 0xC0DECAFE)]
+        pub fn inc(&mut self, by: Value) -> Result<Value, AdderError> {
+            let mut accumulator: AccumulatorRef = FromAccountId::from_account_id(self.acc_contract);
+            let result = accumulator
+                .call_mut()
+                .inc(by)
+                .transferred_value(self.env().transferred_value())
                 .try_invoke();
+            match result {
+                Ok(Ok(Ok(()))) => self.read_accumulator(),
+                Ok(Ok(Err(AccumulatorError::Overflow))) => Err(AdderError::Overflow),
+                Ok(Ok(Err(AccumulatorError::Underflow))) => Err(AdderError::Underflow),
+                _ => Err(AdderError::AccumulatorCallFailed),
+            }
+        }
+
+        /// Reads the current `accumulator` value.
+        fn read_accumulator(&self) -> Result<Value, AdderError> {
+            let accumulator: AccumulatorRef = FromAccountId::from_account_id(self.acc_contract);
+            accumulator
+                .call()
+                .get()
+                .try_invoke()
+                .ok()
+                .and_then(Result::ok)
+                .ok_or(AdderError::AccumulatorCallFailed)
+        }
+
+        /// Increases the value stored under `DELEGATED_VALUE_KEY` by some amount.
+        ///
+        /// This is meant to be delegate called by the `delegator`, in which case the
+        /// value lives in the storage of the `delegator`. It does not write back the
+        /// storage of the `adder` so that the storage of the caller stays intact.
+        ///
+        /// Returns `AdderError::Overflow` if the value would overflow.
+        ///
+        /// It is payable because a delegate call keeps the value transferred to the
+        /// caller, e.g. the fee of `change_delegate`.
+        #[ink(message, payable, selector = 0xC0DECAFD)]
+        pub fn inc_delegated(&self, by: Value) -> Result<(), AdderError> {
+            let value: Value = ink::env::get_contract_storage(&DELEGATED_VALUE_KEY)
+                .ok()
+                .flatten()
+                .unwrap_or_default();
+            let value = value.checked_add(by).ok_or(AdderError::Overflow)?;
+            ink::env::set_contract_storage(&DELEGATED_VALUE_KEY, &value);
+            Ok(())
+        }
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+        use ink::reflect::DispatchableMessageInfo;
+
+        /// Returns the selector and name of the message with the given selector.
+        fn message<const ID: u32>() -> ([u8; 4], &'static str)
+        where
+            Adder: DispatchableMessageInfo<ID>,
+        {
+            (
+                <Adder as DispatchableMessageInfo<ID>>::SELECTOR,
+                <Adder as DispatchableMessageInfo<ID>>::LABEL,
+            )
+        }
+
+        #[ink::test]
+        fn only_owner_can_set_accumulator() {
+            let accounts = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+            let mut adder = Adder::new(accounts.bob);
+            assert_eq!(adder.set_accumulator(accounts.charlie), Ok(()));
+            assert_eq!(adder.accumulator(), accounts.charlie);
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.eve);
+            assert_eq!(
+                adder.set_accumulator(accounts.bob),
+                Err(AdderError::Unauthorized)
+            );
+            assert_eq!(adder.accumulator(), accounts.charlie);
+        }
+
+        #[ink::test]
+        fn only_owner_can_set_owner() {
+            let accounts = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+            let mut adder = Adder::new(accounts.bob);
+            assert_eq!(adder.set_owner(accounts.eve), Ok(()));
+            assert_eq!(adder.owner(), accounts.eve);
+            assert_eq!(
+                adder.set_owner(accounts.alice),
+                Err(AdderError::Unauthorized)
+            );
+            assert_eq!(adder.owner(), accounts.eve);
+        }
+
+        #[test]
+        fn inc_selector_matches() {
+            assert_eq!(
+                message::<{ u32::from_be_bytes(CHANGE_SELECTOR) }>(),
+                (CHANGE_SELECTOR, "inc")
+            );
+        }
+
+        #[test]
+        fn inc_delegated_selector_matches() {
+            assert_eq!(
+                message::<{ u32::from_be_bytes(DELEGATED_CHANGE_SELECTOR) }>(),
+                (DELEGATED_CHANGE_SELECTOR, "inc_delegated")
+            );
         }
     }
 }
@@ -5,4 +5,6 @@ set -eu
 cargo contract build --manifest-path accumulator/Cargo.toml
 cargo contract build --manifest-path adder/Cargo.toml
 cargo contract build --manifest-path subber/Cargo.toml
+cargo contract build --manifest-path reentrant/Cargo.toml
+cargo contract build --manifest-path delegator_v2/Cargo.toml
 cargo contract build
@@ -0,0 +1,68 @@
+#![cfg_attr(not(feature = "std"), no_std)]
+
+pub use self::delegator_v2::{
+    Delegator as DelegatorV2, DelegatorRef as DelegatorV2Ref, Which as WhichV2,
+};
+
+/// A second version of the `delegator`.
+///
+/// Only meant to test `Delegator::upgrade`: it reads the storage the `delegator` left
+/// behind and adds a message the `delegator` does not have.
+#[ink::contract]
+pub mod delegator_v2 {
+    use ink::storage::Lazy;
+
+    /// Mirrors `delegator::Which`, which is encoded the same way.
+    #[derive(Debug, Copy, Clone, PartialEq, Eq, scale::Decode, scale::Encode)]
+    #[cfg_attr(
+        feature = "std",
+        derive(ink::storage::traits::StorageLayout, scale_info::TypeInfo)
+    )]
+    pub enum Which {
+        Adder,
+        Subber,
+    }
+
+    /// The leading fields of the storage of the `delegator`.
+    ///
+    /// The storage struct has to be called `Delegator` for `acc_contract` to get the
+    /// same storage key as in the `delegator`. The remaining fields of the root are
+    /// left undecoded.
+    #[ink(storage)]
+    pub struct Delegator {
+        /// Says which of `adder` or `subber` is currently in use.
+        which: Which,
+        /// The `accumulator` smart contract.
+        acc_contract: Lazy<AccountId>,
+    }
+
+    impl Delegator {
+        /// Only there because a contract needs a constructor; the `delegator_v2` is
+        /// meant to be reached through `Delegator::upgrade`.
+        #[ink(constructor)]
+        pub fn new() -> Self {
+            Self {
+                which: Which::Adder,
+                acc_contract: Lazy::new(),
+            }
+        }
+
+        /// Returns in which state the `delegator` is, like `Delegator::which`.
+        #[ink(message)]
+        pub fn which(&self) -> Which {
+            self.which
+        }
+
+        /// Returns the `accumulator` smart contract, if set.
+        #[ink(message)]
+        pub fn acc_contract(&self) -> Option<AccountId> {
+            self.acc_contract.get()
+        }
+
+        /// Only exists in the `delegator_v2`.
+        #[ink(message)]
+        pub fn is_v2(&self) -> bool {
+            true
+        }
+    }
+}
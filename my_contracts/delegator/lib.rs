@@ -1,11 +1,75 @@
 #![cfg_attr(not(feature = "std"), no_std)]
 
-#[ink::contract]
+/// The environment the `delegator` is built for.
+#[cfg(any(not(test), feature = "e2e-tests"))]
+pub type DelegatorEnvironment = ink::env::DefaultEnvironment;
+
+/// Unit tests build the `delegator` for [`CustomEnvironment`] to prove that it does not
+/// depend on the `DefaultEnvironment`.
+#[cfg(all(test, not(feature = "e2e-tests")))]
+pub type DelegatorEnvironment = CustomEnvironment;
+
+/// An environment distinct from the `DefaultEnvironment`.
+///
+/// The primitive types are the default ones because the `delegator` calls the
+/// `accumulator`, `adder` and `subber`, which are built for the `DefaultEnvironment`.
+#[cfg(all(test, not(feature = "e2e-tests")))]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CustomEnvironment {}
+
+#[cfg(all(test, not(feature = "e2e-tests")))]
+impl ink::env::Environment for CustomEnvironment {
+    const MAX_EVENT_TOPICS: usize =
+        <ink::env::DefaultEnvironment as ink::env::Environment>::MAX_EVENT_TOPICS;
+
+    type AccountId = <ink::env::DefaultEnvironment as ink::env::Environment>::AccountId;
+    type Balance = <ink::env::DefaultEnvironment as ink::env::Environment>::Balance;
+    type Hash = <ink::env::DefaultEnvironment as ink::env::Environment>::Hash;
+    type Timestamp = <ink::env::DefaultEnvironment as ink::env::Environment>::Timestamp;
+    type BlockNumber = <ink::env::DefaultEnvironment as ink::env::Environment>::BlockNumber;
+    type ChainExtension = ink::env::NoChainExtension;
+}
+
+#[ink::contract(env = crate::DelegatorEnvironment)]
 mod caller {
-    use ink::env::{
-        call::{build_call, Call, ExecutionInput, Selector},
-        CallFlags, DefaultEnvironment,
+    use accumulator::{Accumulating, AccumulatorError, AccumulatorRef, Value};
+    use adder::{AdderError, AdderRef};
+    use ink::{
+        codegen::TraitCallBuilder,
+        env::{
+            call::{build_call, ExecutionInput, FromAccountId, Selector},
+            CallFlags, ReturnFlags,
+        },
+        prelude::vec::Vec,
+        storage::{traits::ManualKey, Lazy, Mapping},
+        ToAccountId,
     };
+    use selectors::{
+        CHANGE_SELECTOR, DEC_SELECTOR, DELEGATED_CHANGE_SELECTOR, DELEGATED_VALUE_KEY,
+    };
+    use subber::{SubberError, SubberRef};
+
+    /// The maximum number of deltas `change_many` accepts at once and the maximum
+    /// number of additional accumulators `broadcast_change` changes.
+    pub const MAX_BATCH: usize = 64;
+
+    /// The number of recent changes kept by the `delegator`, see `recent_changes`.
+    pub const HISTORY_LEN: u32 = 16;
+
+    /// The number of recent switches kept by the `delegator`, see `switch_history`.
+    pub const SWITCH_LOG_CAPACITY: u32 = 32;
+
+    /// The version of the `delegator` code, see `version`.
+    ///
+    /// Has to be bumped once for every deployed version of the `delegator` whose
+    /// behavior differs from the version before, so that callers can tell them apart
+    /// after an `upgrade`. Changes between two deployments share a version.
+    pub const CONTRACT_VERSION: u32 = 2;
+
+    /// The version of the storage layout the current code expects, see `migrate`.
+    ///
+    /// Has to be bumped whenever the storage layout changes.
+    pub const STORAGE_VERSION: u32 = 7;
 
     /// Specifies the state of the `delegator` contract.
     ///
@@ -23,16 +87,521 @@ mod caller {
         Subber,
     }
 
+    impl Which {
+        /// Returns the other state.
+        fn other(self) -> Self {
+            match self {
+                Which::Adder => Which::Subber,
+                Which::Subber => Which::Adder,
+            }
+        }
+    }
+
+    /// The contracts the `delegator` delegates to.
+    #[derive(Debug, Copy, Clone, PartialEq, Eq, scale::Decode, scale::Encode)]
+    #[cfg_attr(feature = "std", derive(scale_info::TypeInfo))]
+    pub struct Targets {
+        /// The `accumulator` smart contract.
+        pub acc: AccountId,
+        /// The `adder` smart contract.
+        pub add: AccountId,
+        /// The `subber` smart contract.
+        pub sub: AccountId,
+    }
+
+    /// The settings of a `delegator` created with `new`.
+    #[derive(Debug, Default, Clone, PartialEq, Eq, scale::Decode, scale::Encode)]
+    #[cfg_attr(feature = "std", derive(scale_info::TypeInfo))]
+    pub struct Settings {
+        /// The current value of the `accumulator`, cached as `base_value`.
+        pub init_value: Value,
+        /// The number of blocks that have to pass between two switches.
+        pub switch_cooldown: BlockNumber,
+        /// The accounts that may propose and approve admin operations.
+        pub approvers: Vec<AccountId>,
+        /// The number of approvals an admin operation needs, where `0` lets the
+        /// holders of the respective role perform admin operations directly.
+        pub threshold: u32,
+    }
+
+    /// Counts how the `delegator` has been used.
+    #[derive(Debug, Default, Copy, Clone, PartialEq, Eq, scale::Decode, scale::Encode)]
+    #[cfg_attr(
+        feature = "std",
+        derive(ink::storage::traits::StorageLayout, scale_info::TypeInfo)
+    )]
+    pub struct Stats {
+        /// The number of successful changes routed to the `adder`.
+        pub adder_calls: u64,
+        /// The number of successful changes routed to the `subber`.
+        pub subber_calls: u64,
+        /// The number of failed calls that did not revert the message.
+        pub failed_calls: u64,
+        /// The number of switches between `adder` and `subber`.
+        pub switch_count: u64,
+    }
+
+    /// A switch of the `delegator`, see `switch_history`.
+    #[derive(Debug, Copy, Clone, PartialEq, Eq, scale::Decode, scale::Encode)]
+    #[cfg_attr(
+        feature = "std",
+        derive(ink::storage::traits::StorageLayout, scale_info::TypeInfo)
+    )]
+    pub struct SwitchRecord {
+        /// The state switched to.
+        pub to: Which,
+        /// The account that made the switch.
+        pub by: AccountId,
+        /// The block number of the switch.
+        pub at: BlockNumber,
+    }
+
+    /// Whether the contracts the `delegator` delegates to respond, see `health_check`.
+    #[derive(Debug, Copy, Clone, PartialEq, Eq, scale::Decode, scale::Encode)]
+    #[cfg_attr(feature = "std", derive(scale_info::TypeInfo))]
+    pub struct Health {
+        /// Whether the `accumulator` returned its value.
+        pub accumulator_ok: bool,
+        /// Whether the `adder` returned its `accumulator`.
+        pub adder_ok: bool,
+        /// Whether the `subber` returned its `accumulator`.
+        pub subber_ok: bool,
+    }
+
+    /// Why a call of the `delegator` to another contract failed.
+    #[derive(Debug, Copy, Clone, PartialEq, Eq, scale::Decode, scale::Encode)]
+    #[cfg_attr(feature = "std", derive(scale_info::TypeInfo))]
+    pub enum FailureReason {
+        /// The call could not be made or the called contract trapped or reverted.
+        EnvError,
+        /// The called contract could not dispatch the message.
+        LangError,
+    }
+
+    /// A role that allows calling some of the privileged messages.
+    #[derive(Debug, Copy, Clone, PartialEq, Eq, scale::Decode, scale::Encode)]
+    #[cfg_attr(
+        feature = "std",
+        derive(ink::storage::traits::StorageLayout, scale_info::TypeInfo)
+    )]
+    pub enum Role {
+        /// May grant and revoke roles and configure the `delegator`.
+        Admin,
+        /// May set the contracts the `delegator` delegates to.
+        Manager,
+        /// May pause and unpause the `delegator`.
+        Pauser,
+    }
+
+    /// An admin operation that needs `threshold` approvals once approvers are set.
+    #[derive(Debug, Copy, Clone, PartialEq, Eq, scale::Decode, scale::Encode)]
+    #[cfg_attr(
+        feature = "std",
+        derive(ink::storage::traits::StorageLayout, scale_info::TypeInfo)
+    )]
+    pub enum Action {
+        /// Sets the `adder` smart contract, see `set_adder`.
+        SetAdder(AccountId),
+        /// Replaces the code of the `delegator`, see `upgrade`.
+        Upgrade(Hash),
+        /// Pauses the `delegator`, see `pause`.
+        Pause,
+        /// Terminates the `delegator` in favour of the beneficiary, see `terminate`.
+        Terminate(AccountId),
+        /// Sets the `accumulator` smart contract, see `set_accumulator`.
+        SetAccumulator(AccountId),
+        /// Sets the `subber` smart contract, see `set_subber`.
+        SetSubber(AccountId),
+        /// Sets the code hashes of the `adder` and `subber`, see `set_operator_hashes`.
+        SetOperatorHashes(Hash, Hash),
+        /// Moves the `accumulator` value to a new `accumulator`, see
+        /// `migrate_accumulator`.
+        MigrateAccumulator(AccountId),
+        /// Transfers an amount to an account, see `withdraw`.
+        Withdraw(Balance, AccountId),
+        /// Sets the fee charged for each change, see `set_fee`.
+        SetFee(Balance),
+        /// Transfers the collected fees to an account, see `withdraw_fees`.
+        WithdrawFees(AccountId),
+        /// Sets the gas limit for delegated calls, see `set_gas_limit`.
+        SetGasLimit(u64),
+        /// Sets whether called contracts may call back into the `delegator`, see
+        /// `set_allow_reentry`.
+        SetAllowReentry(bool),
+        /// Grants a role to an account, see `grant_role`.
+        GrantRole(AccountId, Role),
+    }
+
+    /// An `Action` proposed by one of the approvers.
+    #[derive(Debug, Clone, PartialEq, Eq, scale::Decode, scale::Encode)]
+    #[cfg_attr(
+        feature = "std",
+        derive(ink::storage::traits::StorageLayout, scale_info::TypeInfo)
+    )]
+    pub struct Proposal {
+        /// The proposed action.
+        pub action: Action,
+        /// The approvers that approved the action, starting with the proposer.
+        pub approvals: Vec<AccountId>,
+    }
+
+    /// Identifies one of the contracts the `delegator` delegates to.
+    #[derive(Debug, Copy, Clone, PartialEq, Eq, scale::Decode, scale::Encode)]
+    #[cfg_attr(feature = "std", derive(scale_info::TypeInfo))]
+    pub enum Target {
+        Accumulator,
+        Adder,
+        Subber,
+    }
+
+    /// Errors that can occur upon calling the `delegator` contract.
+    #[derive(Debug, Copy, Clone, PartialEq, Eq, scale::Decode, scale::Encode)]
+    #[cfg_attr(feature = "std", derive(scale_info::TypeInfo))]
+    pub enum DelegatorError {
+        /// The `accumulator` could not be reached, e.g. because it is not a contract.
+        AccumulatorUnreachable,
+        /// The `adder` or `subber` could not be reached, e.g. because it is not a contract.
+        OperatorUnreachable(Which),
+        /// The called contract trapped or reverted.
+        TargetReverted,
+        /// The called contract failed to dispatch the message.
+        LangError(ink::LangError),
+        /// The return value of the called contract could not be decoded.
+        DecodeFailed,
+        /// The caller is not allowed to perform this operation.
+        Unauthorized,
+        /// The contract to call has not been set yet.
+        NotConfigured,
+        /// A contract to delegate to is the zero address.
+        InvalidTarget,
+        /// The same contract was given for more than one target.
+        DuplicateTarget,
+        /// More than `MAX_BATCH` deltas were given at once.
+        BatchTooLarge,
+        /// The delta at the given index could not be applied.
+        BatchFailedAt(u32),
+        /// The delegated call trapped while a gas limit was set, which is how running
+        /// out of gas surfaces.
+        ///
+        /// The environment does not tell why a call trapped, so any other trap of a
+        /// call with a gas limit is reported as `OutOfGas` as well.
+        OutOfGas,
+        /// A value kept by the `delegator` would overflow the bounds of its type.
+        Overflow,
+        /// The code of the `delegator` could not be replaced.
+        UpgradeFailed,
+        /// The `delegator` is paused.
+        Paused,
+        /// The delta cannot be negated, which falling back to the other operator needs.
+        DeltaOutOfRange,
+        /// Switches have to be scheduled, see `schedule_switch`.
+        TimelockRequired,
+        /// The scheduled switch cannot be executed yet.
+        TimelockNotExpired,
+        /// No switch has been scheduled.
+        NoPendingSwitch,
+        /// The `delegator` was switched less than `switch_cooldown` blocks ago.
+        CooldownActive {
+            /// The number of blocks until the `delegator` can be switched again.
+            remaining: BlockNumber,
+        },
+        /// The operation has to be proposed and approved, see `propose`.
+        ApprovalRequired,
+        /// The approvers contain duplicates or fewer than `threshold` accounts.
+        InvalidApprovers,
+        /// There is no pending proposal with the given id.
+        UnknownProposal,
+        /// The caller already approved the proposal.
+        AlreadyApproved,
+        /// The proposal has fewer than `threshold` approvals.
+        ThresholdNotReached,
+        /// The allowlist is enabled and the caller is not on it.
+        NotAllowed,
+        /// A switch has been scheduled, see `schedule_switch`.
+        SwitchPending,
+        /// The transfer out of the `delegator` failed, e.g. due to insufficient free
+        /// balance.
+        WithdrawFailed,
+        /// The storage is already at `STORAGE_VERSION`.
+        AlreadyMigrated,
+        /// A change was attempted while another change is in progress.
+        Reentrancy,
+        /// The target of `raw_call` could not be reached.
+        TargetUnreachable,
+        /// The given account is not a contract.
+        NotAContract(AccountId),
+        /// The absolute value of the delta exceeds `max_delta`.
+        DeltaTooLarge {
+            /// The largest absolute delta accepted.
+            max: u32,
+        },
+        /// Less than `fee` was transferred along with the change.
+        InsufficientFee {
+            /// The fee that has to be transferred.
+            required: Balance,
+        },
+        /// The environment failed the call to the `adder` or `subber` for another
+        /// reason than the called contract being unreachable, trapping, reverting or
+        /// returning undecodable data, e.g. because the transfer failed.
+        EnvFailure,
+        /// The `accumulator` rejected the change.
+        AccumulatorRejected(AccumulatorError),
+        /// The message cannot charge a fee, so it is not available while `fee` is set.
+        FeeNotSupported,
+    }
+
+    /// Type alias for the contract's `Result` type.
+    pub type Result<T> = core::result::Result<T, DelegatorError>;
+
+    impl DelegatorError {
+        /// Maps an environmental error of a cross-contract call into a `DelegatorError`,
+        /// using `unreachable` for failures to reach the called contract.
+        fn from_env(error: ink::env::Error, unreachable: Self) -> Self {
+            match error {
+                ink::env::Error::Decode(_) => DelegatorError::DecodeFailed,
+                ink::env::Error::CalleeTrapped | ink::env::Error::CalleeReverted => {
+                    DelegatorError::TargetReverted
+                }
+                _ => unreachable,
+            }
+        }
+    }
+
+    /// Emitted when an admin scheduled a switch, see `schedule_switch`.
+    #[ink(event)]
+    pub struct SwitchScheduled {
+        /// The state to switch to.
+        #[ink(topic)]
+        target: Which,
+        /// The block number from which the switch can be executed.
+        executable_at: BlockNumber,
+    }
+
+    /// Emitted when a scheduled switch has been executed.
+    #[ink(event)]
+    pub struct SwitchExecuted {
+        /// The state switched to.
+        #[ink(topic)]
+        target: Which,
+    }
+
+    /// Emitted when an admin cancelled a scheduled switch.
+    #[ink(event)]
+    pub struct SwitchCancelled {
+        /// The state that would have been switched to.
+        #[ink(topic)]
+        target: Which,
+    }
+
+    /// Emitted when the `delegator` switches between `adder` and `subber`.
+    #[ink(event)]
+    pub struct Switched {
+        /// The state before the switch.
+        #[ink(topic)]
+        from: Which,
+        /// The state after the switch.
+        #[ink(topic)]
+        to: Which,
+        /// The account that triggered the switch.
+        #[ink(topic)]
+        by: AccountId,
+    }
+
+    /// Emitted when the `delegator` successfully routed a change to the `adder` or `subber`.
+    #[ink(event)]
+    pub struct Changed {
+        /// The account that requested the change.
+        #[ink(topic)]
+        caller: AccountId,
+        /// The contract the change was routed to.
+        #[ink(topic)]
+        via: Which,
+        /// The amount the change was requested for.
+        by: Value,
+        /// The `accumulator` value after the change.
+        new_value: Value,
+    }
+
+    /// Emitted when a manager replaced one of the contracts the `delegator` delegates to.
+    #[ink(event)]
+    pub struct TargetUpdated {
+        /// The contract that was replaced.
+        #[ink(topic)]
+        which_target: Target,
+        /// The previous address of the contract.
+        old: AccountId,
+        /// The new address of the contract.
+        new: AccountId,
+    }
+
+    /// Emitted when an admin moved the `accumulator` value to a new `accumulator`.
+    #[ink(event)]
+    pub struct AccumulatorMigrated {
+        /// The previous `accumulator`.
+        #[ink(topic)]
+        old: AccountId,
+        /// The new `accumulator`.
+        #[ink(topic)]
+        new: AccountId,
+        /// The value that was moved.
+        value: Value,
+    }
+
+    /// Emitted when a call to another contract failed without reverting the message.
+    ///
+    /// Failures that make a message return an error revert the message, including
+    /// its events, so this is only emitted by `try_change` and `broadcast_change`.
+    #[ink(event)]
+    pub struct DelegationFailed {
+        /// The called contract.
+        #[ink(topic)]
+        target: AccountId,
+        /// The selector of the called message.
+        selector: [u8; 4],
+        /// Why the call failed.
+        reason: FailureReason,
+    }
+
+    /// Emitted when a change fell back to the other operator because the call to
+    /// the operator in use failed.
+    #[ink(event)]
+    pub struct FellBack {
+        /// The operator in use, whose call failed.
+        from: Which,
+        /// The operator the change fell back to.
+        to: Which,
+    }
+
+    /// Emitted when an admin reset the `accumulator` value to zero.
+    #[ink(event)]
+    pub struct Reset {
+        /// The account that reset the value.
+        #[ink(topic)]
+        by: AccountId,
+    }
+
+    /// Emitted when an admin granted a role to an account.
+    #[ink(event)]
+    pub struct RoleGranted {
+        /// The account that was granted the role.
+        #[ink(topic)]
+        account: AccountId,
+        /// The granted role.
+        role: Role,
+        /// The admin that granted the role.
+        #[ink(topic)]
+        by: AccountId,
+    }
+
+    /// Emitted when an admin revoked a role from an account.
+    #[ink(event)]
+    pub struct RoleRevoked {
+        /// The account the role was revoked from.
+        #[ink(topic)]
+        account: AccountId,
+        /// The revoked role.
+        role: Role,
+        /// The admin that revoked the role.
+        #[ink(topic)]
+        by: AccountId,
+    }
+
+    /// Emitted when the ownership of the `delegator` has been transferred.
+    #[ink(event)]
+    pub struct OwnershipTransferred {
+        /// The previous owner.
+        #[ink(topic)]
+        old: AccountId,
+        /// The new owner.
+        #[ink(topic)]
+        new: AccountId,
+    }
+
+    impl From<AdderError> for DelegatorError {
+        fn from(error: AdderError) -> Self {
+            match error {
+                AdderError::Overflow => AccumulatorError::Overflow.into(),
+                AdderError::Underflow => AccumulatorError::Underflow.into(),
+                AdderError::AccumulatorCallFailed => DelegatorError::AccumulatorUnreachable,
+                AdderError::Unauthorized => DelegatorError::Unauthorized,
+            }
+        }
+    }
+
+    impl From<AccumulatorError> for DelegatorError {
+        fn from(error: AccumulatorError) -> Self {
+            DelegatorError::AccumulatorRejected(error)
+        }
+    }
+
+    impl From<SubberError> for DelegatorError {
+        fn from(error: SubberError) -> Self {
+            match error {
+                SubberError::Overflow => AccumulatorError::Overflow.into(),
+                SubberError::Underflow => AccumulatorError::Underflow.into(),
+                SubberError::AccumulatorCallFailed => DelegatorError::AccumulatorUnreachable,
+                SubberError::Unauthorized => DelegatorError::Unauthorized,
+            }
+        }
+    }
+
+    /// Emitted when an admin changed whether called contracts may call back into the
+    /// `delegator`.
+    #[ink(event)]
+    pub struct ReentryPolicyChanged {
+        /// Whether called contracts may call back into the `delegator`.
+        allow: bool,
+    }
+
+    /// Emitted when an admin changed the largest absolute delta accepted.
+    #[ink(event)]
+    pub struct MaxDeltaChanged {
+        /// The largest absolute delta accepted, where `0` accepts any delta.
+        max_delta: u32,
+    }
+
+    /// Emitted when an admin replaced the code of the `delegator`.
+    #[ink(event)]
+    pub struct Upgraded {
+        /// The code hash before the upgrade.
+        old_hash: Hash,
+        /// The code hash after the upgrade.
+        new_hash: Hash,
+    }
+
+    /// Emitted when a pauser paused the `delegator`.
+    #[ink(event)]
+    pub struct Paused {
+        /// The account that paused the `delegator`.
+        #[ink(topic)]
+        by: AccountId,
+    }
+
+    /// Emitted when a pauser unpaused the `delegator`.
+    #[ink(event)]
+    pub struct Unpaused {
+        /// The account that unpaused the `delegator`.
+        #[ink(topic)]
+        by: AccountId,
+    }
+
+    /// Emitted when the `delegator` was called with an unknown selector, see `fallback`.
+    #[ink(event)]
+    pub struct UnknownSelector {
+        /// The account that made the call.
+        #[ink(topic)]
+        caller: AccountId,
+    }
+
     /// Delegates calls to an `adder` or `subber` contract to mutate
     /// a value in an `accumulator` contract.
     ///
     /// # Note
     ///
-    /// In order to instantiate the `delegator` smart contract we first
-    /// have to manually put the code of the `accumulator`, `adder`
-    /// and `subber` smart contracts, receive their code hashes from
-    /// the signalled events and put their code hash into our
-    /// `delegator` smart contract.
+    /// The `delegator` smart contract can either be instantiated with `new`
+    /// from already instantiated `accumulator`, `adder` and `subber` smart
+    /// contracts, or with `new_from_hashes`, which instantiates and wires all
+    /// three from the code hashes of their uploaded code in one go.
     ///
     /// The `AccumulatorRef`, `AdderRef` and `SubberRef` are smart contract
     /// reference types that have been automatically generated by ink!.
@@ -41,136 +610,4794 @@ mod caller {
         /// Says which of `adder` or `subber` is currently in use.
         which: Which,
         /// The `accumulator` smart contract.
-        acc_contract: AccountId,
+        ///
+        /// The targets are kept in a `Lazy` each so that they are only loaded by the
+        /// messages that call them, see `acc_contract`. The e2e test
+        /// `switch_does_not_load_targets` prints what this saves per `switch`.
+        acc_contract: Lazy<AccountId>,
+        /// Additional `accumulator` smart contracts changed by `broadcast_change`.
+        accumulators: Vec<AccountId>,
         /// The `adder` smart contract.
-        add_contract: AccountId,
+        add_contract: Lazy<AccountId>,
         /// The `subber` smart contract.
-        sub_contract: AccountId,
+        sub_contract: Lazy<AccountId>,
+        /// The code hash of the `adder` smart contract.
+        add_code_hash: Hash,
+        /// The code hash of the `subber` smart contract.
+        sub_code_hash: Hash,
+        /// The account owning the `delegator`, which holds the `Admin` role.
+        owner: AccountId,
+        /// The roles held by each account.
+        roles: Mapping<(AccountId, Role), ()>,
+        /// The account proposed to become the new owner, if any.
+        pending_owner: Option<AccountId>,
+        /// The gas limit for delegated calls, where `0` forwards all remaining gas.
+        gas_limit: u64,
+        /// Whether called contracts may call back into the `delegator`.
+        allow_reentry: bool,
+        /// The largest absolute delta accepted, where `0` accepts any delta.
+        max_delta: u32,
+        /// The fee charged for each `change`.
+        fee: Balance,
+        /// The fees charged since they were last withdrawn with `withdraw_fees`.
+        collected_fees: Balance,
+        /// Whether changes fall back to the other operator if the call to the
+        /// operator in use fails.
+        fallback_enabled: bool,
+        /// Whether changes and switches are blocked.
+        paused: bool,
+        /// The `accumulator` value as last seen by the `delegator`, starting from the
+        /// initial value given on instantiation.
+        ///
+        /// Set back to zero by `reset`.
+        base_value: Value,
+        /// The caller, delta and block number of the last successful change, if any.
+        last_change: Option<(AccountId, Value, BlockNumber)>,
+        /// Counts how the `delegator` has been used.
+        stats: Stats,
+        /// The last `HISTORY_LEN` changes, where the change number `n` is kept in
+        /// slot `n % HISTORY_LEN` and overwrites the change `HISTORY_LEN` before it.
+        history: Mapping<u32, (Which, Value)>,
+        /// The number of changes recorded in `history` so far.
+        history_count: u32,
+        /// The net change of the `accumulator` value made by each caller.
+        contributions: Mapping<AccountId, Value>,
+        /// The net change of the `accumulator` value made by all callers.
+        total_net: Value,
+        /// The number of blocks between scheduling a switch and being able to execute
+        /// it, where `0` allows switching directly.
+        switch_delay: BlockNumber,
+        /// The scheduled switch and the block number from which it can be executed.
+        pending_switch: Option<(Which, BlockNumber)>,
+        /// The number of blocks that have to pass between two switches.
+        switch_cooldown: BlockNumber,
+        /// The block number of the last switch.
+        last_switch_block: BlockNumber,
+        /// The last `SWITCH_LOG_CAPACITY` switches, where the switch number `n` is kept
+        /// in slot `n % SWITCH_LOG_CAPACITY` and overwrites the oldest one.
+        switch_log: Mapping<u32, SwitchRecord>,
+        /// The number of switches recorded in `switch_log` so far.
+        switch_log_head: u32,
+        /// The accounts that may propose and approve admin operations.
+        approvers: Vec<AccountId>,
+        /// The number of approvals an admin operation needs, where `0` lets the holders
+        /// of the respective role perform admin operations directly.
+        threshold: u32,
+        /// The pending proposals by id.
+        proposals: Mapping<u32, Proposal>,
+        /// The id of the next proposal.
+        next_proposal_id: u32,
+        /// Whether only accounts on the allowlist may change the value.
+        allowlist_enabled: bool,
+        /// The accounts allowed to change the value while the allowlist is enabled.
+        allowed: Mapping<AccountId, ()>,
+        /// The version of the storage layout, see `migrate`.
+        storage_version: u32,
+        /// Whether a change is in progress, see `delegate_change_with`.
+        ///
+        /// Kept in a `Lazy` because it is written to storage right away, so a reentrant
+        /// call sees it before the outer message returns.
+        in_call: Lazy<bool>,
+        /// The value changed by `change_delegate`.
+        ///
+        /// Kept under the key the `adder` and `subber` code expects when delegate called.
+        value: Lazy<Value, ManualKey<DELEGATED_VALUE_KEY>>,
     }
 
     impl Delegator {
-        #[ink(constructor)]
+        /// Creates a `delegator` delegating to the given contracts.
+        ///
+        /// The code hashes of the `adder` and `subber` are used by `change_delegate`.
+        /// The `init_value` of the `settings` has to be the current value of the
+        /// `accumulator`.
+        ///
+        /// With a non-zero `threshold`, the admin operations listed in `Action` need
+        /// `threshold` of the `approvers` to approve them, see `propose`.
+        ///
+        /// Fails with `DelegatorError::InvalidTarget` if any of the contracts is the
+        /// zero address, with `DelegatorError::NotAContract` if any of them is not a
+        /// contract, with `DelegatorError::DuplicateTarget` if any two of them
+        /// are the same and with `DelegatorError::InvalidApprovers` if the `approvers`
+        /// contain duplicates or fewer than `threshold` accounts.
+        ///
+        /// Any transferred value is kept by the `delegator` until it is terminated.
+        #[ink(constructor, payable)]
         pub fn new(
             acc_contract: AccountId,
             add_contract: AccountId,
             sub_contract: AccountId,
-        ) -> Self {
-            Delegator {
-                which: Which::Adder,
+            add_code_hash: Hash,
+            sub_code_hash: Hash,
+            settings: Settings,
+        ) -> Result<Self> {
+            for contract in [acc_contract, add_contract, sub_contract] {
+                if contract == unconfigured() {
+                    return Err(DelegatorError::InvalidTarget);
+                }
+                ensure_contract(contract)?;
+            }
+            if acc_contract == add_contract
+                || acc_contract == sub_contract
+                || add_contract == sub_contract
+            {
+                return Err(DelegatorError::DuplicateTarget);
+            }
+            let Settings {
+                init_value,
+                switch_cooldown,
+                approvers,
+                threshold,
+            } = settings;
+            let has_duplicates = approvers
+                .iter()
+                .enumerate()
+                .any(|(index, approver)| approvers[..index].contains(approver));
+            if has_duplicates || approvers.len() < threshold as usize {
+                return Err(DelegatorError::InvalidApprovers);
+            }
+            let mut delegator = Self::init(
                 acc_contract,
                 add_contract,
                 sub_contract,
+                add_code_hash,
+                sub_code_hash,
+                init_value,
+            );
+            delegator.switch_cooldown = switch_cooldown;
+            delegator.approvers = approvers;
+            delegator.threshold = threshold;
+            Ok(delegator)
+        }
+
+        /// Creates a `delegator` by instantiating the `accumulator`, `adder` and
+        /// `subber` from the given code hashes.
+        ///
+        /// The `version` is used as salt, so that multiple `delegator` systems can
+        /// be instantiated from the same code hashes. Each contract is endowed with
+        /// a quarter of the transferred value.
+        ///
+        /// The `delegator` instantiates the `adder` and `subber` and therefore owns
+        /// them, so their `accumulator` can only be replaced with
+        /// `migrate_accumulator` afterwards.
+        ///
+        /// # Panics
+        ///
+        /// If any of the instantiations fails.
+        #[ink(constructor, payable)]
+        pub fn new_from_hashes(
+            acc_hash: Hash,
+            add_hash: Hash,
+            sub_hash: Hash,
+            init_value: Value,
+            version: u32,
+        ) -> Self {
+            let total_balance = Self::env().transferred_value();
+            let salt = version.to_le_bytes();
+            let accumulator = AccumulatorRef::new(init_value)
+                .endowment(total_balance / 4)
+                .code_hash(acc_hash)
+                .salt_bytes(salt)
+                .instantiate();
+            let acc_contract = accumulator.to_account_id();
+            let adder = AdderRef::new(acc_contract)
+                .endowment(total_balance / 4)
+                .code_hash(add_hash)
+                .salt_bytes(salt)
+                .instantiate();
+            let subber = SubberRef::new(acc_contract)
+                .endowment(total_balance / 4)
+                .code_hash(sub_hash)
+                .salt_bytes(salt)
+                .instantiate();
+            Self::init(
+                acc_contract,
+                adder.to_account_id(),
+                subber.to_account_id(),
+                add_hash,
+                sub_hash,
+                init_value,
+            )
+        }
+
+        /// Creates a `delegator` without any contracts to delegate to.
+        ///
+        /// A manager has to set the `accumulator`, `adder` and `subber` before
+        /// `get` and `change` can be used.
+        #[ink(constructor)]
+        pub fn default() -> Self {
+            Self::init(
+                unconfigured(),
+                unconfigured(),
+                unconfigured(),
+                Hash::default(),
+                Hash::default(),
+                0,
+            )
+        }
+
+        /// Initializes the storage of the `delegator`.
+        ///
+        /// The caller becomes the owner and is granted all roles.
+        fn init(
+            acc_contract: AccountId,
+            add_contract: AccountId,
+            sub_contract: AccountId,
+            add_code_hash: Hash,
+            sub_code_hash: Hash,
+            base_value: Value,
+        ) -> Self {
+            let owner = Self::env().caller();
+            let mut roles = Mapping::default();
+            for role in [Role::Admin, Role::Manager, Role::Pauser] {
+                roles.insert((owner, role), &());
+            }
+            let mut delegator = Delegator {
+                which: Which::Adder,
+                acc_contract: Lazy::new(),
+                accumulators: Vec::new(),
+                add_contract: Lazy::new(),
+                sub_contract: Lazy::new(),
+                add_code_hash,
+                sub_code_hash,
+                owner,
+                roles,
+                pending_owner: None,
+                gas_limit: 0,
+                allow_reentry: false,
+                max_delta: 0,
+                fee: 0,
+                collected_fees: 0,
+                fallback_enabled: false,
+                paused: false,
+                base_value,
+                last_change: None,
+                stats: Stats::default(),
+                history: Mapping::default(),
+                history_count: 0,
+                contributions: Mapping::default(),
+                total_net: 0,
+                switch_delay: 0,
+                pending_switch: None,
+                switch_cooldown: 0,
+                last_switch_block: 0,
+                switch_log: Mapping::default(),
+                switch_log_head: 0,
+                approvers: Vec::new(),
+                threshold: 0,
+                proposals: Mapping::default(),
+                next_proposal_id: 0,
+                allowlist_enabled: false,
+                allowed: Mapping::default(),
+                storage_version: STORAGE_VERSION,
+                in_call: Lazy::new(),
+                value: Lazy::new(),
+            };
+            delegator.acc_contract.set(&acc_contract);
+            delegator.add_contract.set(&add_contract);
+            delegator.sub_contract.set(&sub_contract);
+            delegator
+        }
+
+        /// Returns the current value of the `accumulator`.
+        #[ink(message)]
+        pub fn get(&self) -> Result<Value> {
+            self.read_accumulator(self.call_flags())
+        }
+
+        /// Returns the current value of the `accumulator` without mutating any state.
+        ///
+        /// Unlike `get`, the `accumulator` is never allowed to call back into the
+        /// `delegator`, regardless of `allow_reentry`, which makes this a pure read
+        /// that is cheap to dry-run, e.g. for gas estimation.
+        #[ink(message)]
+        pub fn peek(&self) -> Result<Value> {
+            self.read_accumulator(CallFlags::default())
+        }
+
+        /// Checks that the `accumulator`, `adder` and `subber` respond to a read-only
+        /// call, without mutating any state.
+        ///
+        /// A contract that cannot be called, e.g. because it is not configured or not
+        /// a contract at all, is reported as `false` rather than failing the check.
+        /// Meant to be dry-run before relying on a freshly configured `delegator`.
+        #[ink(message)]
+        pub fn health_check(&self) -> Health {
+            let accumulator: AccumulatorRef = FromAccountId::from_account_id(self.acc_contract());
+            let adder: AdderRef = FromAccountId::from_account_id(self.add_contract());
+            let subber: SubberRef = FromAccountId::from_account_id(self.sub_contract());
+            Health {
+                accumulator_ok: matches!(accumulator.call().get().try_invoke(), Ok(Ok(_))),
+                adder_ok: matches!(adder.call().accumulator().try_invoke(), Ok(Ok(_))),
+                subber_ok: matches!(subber.call().accumulator().try_invoke(), Ok(Ok(_))),
             }
         }
 
+        /// Loads the `accumulator` smart contract.
+        ///
+        /// Returns the unconfigured address if it has never been set.
+        fn acc_contract(&self) -> AccountId {
+            self.acc_contract.get().unwrap_or_else(unconfigured)
+        }
+
+        /// Loads the `adder` smart contract.
+        fn add_contract(&self) -> AccountId {
+            self.add_contract.get().unwrap_or_else(unconfigured)
+        }
+
+        /// Loads the `subber` smart contract.
+        fn sub_contract(&self) -> AccountId {
+            self.sub_contract.get().unwrap_or_else(unconfigured)
+        }
+
+        /// Reads the current value of the `accumulator`.
+        fn read_accumulator(&self, call_flags: CallFlags) -> Result<Value> {
+            let acc_contract = self.acc_contract();
+            ensure_configured(acc_contract)?;
+            let accumulator: AccumulatorRef = FromAccountId::from_account_id(acc_contract);
+            accumulator
+                .call()
+                .get()
+                .call_flags(call_flags)
+                .try_invoke()
+                .map_err(|error| {
+                    DelegatorError::from_env(error, DelegatorError::AccumulatorUnreachable)
+                })?
+                .map_err(DelegatorError::LangError)
+        }
+
+        /// Delegates the change of the `accumulator` value to the `adder` or `subber`,
+        /// depending on the current state, and returns the new `accumulator` value.
+        ///
+        /// Returns `DelegatorError::NotConfigured` if the `accumulator` or the target
+        /// contract has not been set,
+        /// `DelegatorError::OperatorUnreachable` if the call could not be dispatched,
+        /// `DelegatorError::TargetReverted` if the target contract rejected the call,
+        /// `DelegatorError::LangError` if the target contract failed to dispatch the
+        /// message, `DelegatorError::EnvFailure` if the call failed otherwise,
+        /// `DelegatorError::AccumulatorRejected` if the `accumulator` value would
+        /// overflow or drop below its minimum value and
+        /// `DelegatorError::AccumulatorUnreachable` if the target contract could not
+        /// change or read back the `accumulator` value. Returns
+        /// `DelegatorError::NotAllowed` if the caller is not on the enabled allowlist.
+        ///
+        /// At least `fee` has to be transferred along, otherwise
+        /// `DelegatorError::InsufficientFee` is returned. The `delegator` keeps the
+        /// fee and forwards any transferred value above it to the `adder` or `subber`,
+        /// which forward it to the `accumulator`; nothing is refunded. If the change
+        /// fails, the transfer is reverted.
+        ///
+        /// With `fallback_enabled`, a failed call is retried with the other operator
+        /// and the negated delta, and `DelegatorError::DeltaOutOfRange` is returned if
+        /// the delta cannot be negated.
+        ///
+        /// Returns `DelegatorError::DeltaTooLarge` if the absolute value of `by`
+        /// exceeds `max_delta`, which applies to every message changing the value.
+        ///
+        /// Emits a `Changed` event on success.
+        #[ink(message, payable)]
+        pub fn change(&mut self, by: Value) -> Result<Value> {
+            self.ensure_not_paused()?;
+            self.ensure_allowed()?;
+            self.ensure_delta_allowed(by)?;
+            let value = self.charge_fee(1)?;
+            self.delegate_change(by, self.gas_limit, value)
+        }
+
+        /// Like `change`, but takes the delta as an `i64`.
+        ///
+        /// Every `i64` fits into a `Value`, so the delta is forwarded as is through the
+        /// shared selectors and `DelegatorError::DeltaOutOfRange` is never returned for
+        /// the conversion itself.
+        #[ink(message, payable)]
+        pub fn change64(&mut self, by: i64) -> Result<Value> {
+            self.change(Value::from(by))
+        }
+
+        /// Like `change`, but always falls back to the other operator if the call to
+        /// the operator in use fails, regardless of `fallback_enabled`.
+        ///
+        /// The other operator is called with the negated delta so that the change has
+        /// the same effect: if the `adder` fails to add `by`, the `subber` subtracts
+        /// `-by`, and vice versa. The operator in use is not switched. Returns
+        /// `DelegatorError::DeltaOutOfRange` before any call if `by` cannot be negated,
+        /// i.e. is `Value::MIN`, and the error of the first call if both calls fail.
+        ///
+        /// Returns the operator that made the change. The `fee` is charged like in
+        /// `change`.
+        #[ink(message, payable)]
+        pub fn change_with_fallback(&mut self, by: Value) -> Result<Which> {
+            self.ensure_not_paused()?;
+            self.ensure_allowed()?;
+            self.ensure_delta_allowed(by)?;
+            let value = self.charge_fee(1)?;
+            self.delegate_change_with(by, self.gas_limit, value, true)
+                .map(|(via, _)| via)
+        }
+
+        /// Like `change`, but with an explicit gas limit for the delegated call
+        /// instead of the configured one.
+        ///
+        /// `None` uses the configured gas limit and `Some(0)` forwards all remaining gas.
+        /// Returns `DelegatorError::OutOfGas` if the delegated call trapped while
+        /// limited, which is how running out of gas surfaces. Since the environment
+        /// does not tell why a call trapped, a call that traps for another reason
+        /// while limited is reported as `OutOfGas` too. The `fee` is charged like in
+        /// `change`.
+        #[ink(message, payable)]
+        pub fn change_with_gas(&mut self, by: Value, gas_limit: Option<u64>) -> Result<Value> {
+            self.ensure_not_paused()?;
+            self.ensure_allowed()?;
+            self.ensure_delta_allowed(by)?;
+            let value = self.charge_fee(1)?;
+            self.delegate_change(by, gas_limit.unwrap_or(self.gas_limit), value)
+        }
+
+        /// Returns an estimate of the gas the `adder` or `subber` consumes to change
+        /// the `accumulator` value by `by`.
+        ///
+        /// The change is made like `change` would make it and then reverted along
+        /// with the rest of the message, so the `accumulator` value stays the same
+        /// even if this is submitted as a transaction. Only the gas consumed by the
+        /// delegated call is measured, as the difference of `gas_left` before and
+        /// after it: the checks and bookkeeping of `change` and the base cost of the
+        /// transaction are not included. This is a best-effort approximation for
+        /// front-ends; an exact figure for a whole `change` can only be obtained by
+        /// dry running it. A failing call is measured all the same.
         #[ink(message)]
-        pub fn get(&self) {
-            let method_selector = [0xC0, 0xDE, 0xCA, 0xF1];
-            let _result = build_call::<<Self as ::ink::env::ContractEnv>::Env>()
-                .call(self.acc_contract)
-                .call_flags(CallFlags::default())
-                .exec_input(ExecutionInput::new(method_selector.into()))
-                .returns::<()>()
-                .try_invoke();
+        pub fn estimate_change_gas(&self, by: Value) -> u64 {
+            let gas_left = self.env().gas_left();
+            let _ = self.call_operator(self.which, by, self.gas_limit, 0, self.call_flags());
+            let estimate = gas_left.saturating_sub(self.env().gas_left());
+            // Reverts the change but still returns the estimate, encoded the way ink!
+            // encodes the return value of any message.
+            ink::env::return_value::<ink::MessageResult<u64>>(
+                ReturnFlags::new_with_reverted(true),
+                &Ok(estimate),
+            )
         }
 
+        /// Hands the change of the `accumulator` value off to the `adder` or `subber`,
+        /// depending on the current state, as a tail call.
+        ///
+        /// Unlike `change`, the `delegator` does not resume once the `adder` or
+        /// `subber` returned: no `Changed` event is emitted and the caller receives
+        /// the return value of the `adder` or `subber` instead of a `Result` of the
+        /// `delegator`. Errors are only returned by the `delegator` itself if the call
+        /// could not be made at all.
+        ///
+        /// # Note
+        ///
+        /// The declared return type, as listed in the metadata, is only what is returned
+        /// when the call could not be made. Otherwise the caller receives the encoded
+        /// `Result<Value, AdderError>` or `Result<Value, SubberError>` of the operator,
+        /// wrapped in a `MessageResult`, and has to decode it as such.
+        ///
+        /// As the `delegator` does not resume, it cannot keep a fee. Returns
+        /// `DelegatorError::FeeNotSupported` while `fee` is set.
         #[ink(message)]
-        pub fn change(&self, by: i32) {
-            let method_selector = [0xC0, 0xDE, 0xCA, 0xFE];
-            let contract = match self.which {
-                Which::Adder => self.add_contract,
-                Which::Subber => self.sub_contract,
+        pub fn change_tail(&self, by: Value) -> Result<()> {
+            if self.fee > 0 {
+                return Err(DelegatorError::FeeNotSupported);
+            }
+            self.ensure_not_paused()?;
+            self.ensure_allowed()?;
+            self.ensure_delta_allowed(by)?;
+            let call_flags = self.call_flags().set_tail_call(true);
+            self.call_operator(self.which, by, self.gas_limit, 0, call_flags)
+                .map(|_| ())
+        }
+
+        /// Like `change`, but returns a `LangError` of the `adder` or `subber` as the
+        /// inner result instead of as `DelegatorError::LangError`.
+        ///
+        /// This tells apart calls that could not be made or were rejected by the
+        /// `adder` or `subber`, which are returned as the outer error, from calls that
+        /// reached the `adder` or `subber` but could not be dispatched, e.g. because
+        /// the message was not found. The `base_value` is only updated and a
+        /// `Changed` event only emitted if both results are `Ok`; a `DelegationFailed`
+        /// event is emitted for a `LangError`. The `fee` is charged like in `change`.
+        #[ink(message, payable)]
+        pub fn try_change(
+            &mut self,
+            by: Value,
+        ) -> Result<core::result::Result<(), ink::LangError>> {
+            self.ensure_not_paused()?;
+            self.ensure_allowed()?;
+            self.ensure_delta_allowed(by)?;
+            let value = self.charge_fee(1)?;
+            self.guarded(|delegator| delegator.make_try_change(by, value))
+        }
+
+        /// Makes the change for `try_change`, forwarding `value`.
+        fn make_try_change(
+            &mut self,
+            by: Value,
+            value: Balance,
+        ) -> Result<core::result::Result<(), ink::LangError>> {
+            let new_value = match self.try_call_operator(
+                self.which,
+                by,
+                self.gas_limit,
+                value,
+                self.call_flags(),
+            )? {
+                Ok(new_value) => new_value,
+                Err(error) => {
+                    self.stats.failed_calls += 1;
+                    let target = match self.which {
+                        Which::Adder => self.add_contract(),
+                        Which::Subber => self.sub_contract(),
+                    };
+                    self.env().emit_event(DelegationFailed {
+                        target,
+                        selector: CHANGE_SELECTOR,
+                        reason: FailureReason::LangError,
+                    });
+                    return Ok(Err(error));
+                }
+            };
+            self.record_change(self.which, by, new_value);
+            Ok(Ok(()))
+        }
+
+        /// Delegates each of the `deltas` in turn, like `change` does, and returns
+        /// the number of deltas applied.
+        ///
+        /// Returns `DelegatorError::DeltaTooLarge` before any call if any of the
+        /// deltas exceeds `max_delta`. Otherwise stops at the first delta that cannot
+        /// be applied and returns `DelegatorError::BatchFailedAt` with its index.
+        /// Since returning an error reverts the message, none of the deltas are
+        /// applied in that case.
+        ///
+        /// Returns `DelegatorError::BatchTooLarge` if more than `MAX_BATCH` deltas
+        /// are given.
+        ///
+        /// The `fee` is charged for each of the deltas. Any transferred value above
+        /// the fees is kept by the `delegator`, as with `deposit`.
+        #[ink(message, payable)]
+        pub fn change_many(&mut self, deltas: Vec<Value>) -> Result<u32> {
+            self.ensure_not_paused()?;
+            self.ensure_allowed()?;
+            if deltas.len() > MAX_BATCH {
+                return Err(DelegatorError::BatchTooLarge);
+            }
+            for &by in &deltas {
+                self.ensure_delta_allowed(by)?;
+            }
+            let deltas_len = deltas.len() as u32;
+            self.charge_fee(deltas_len)?;
+            for (index, by) in deltas.into_iter().enumerate() {
+                self.delegate_change(by, self.gas_limit, 0)
+                    .map_err(|_| DelegatorError::BatchFailedAt(index as u32))?;
+            }
+            Ok(deltas_len)
+        }
+
+        /// Changes the value of every accumulator registered with `add_accumulator` by
+        /// `by` and returns the number of accumulators that were changed.
+        ///
+        /// The accumulators are called directly with `inc` in `adder` state and with
+        /// `dec` in `subber` state, so the effect is the same as that of `change`. The
+        /// `accumulator` used by `change` is not included.
+        ///
+        /// A failing accumulator does not stop the broadcast: it is skipped and its
+        /// change is reverted, while the changes of the other accumulators are kept.
+        /// A `DelegationFailed` event is emitted for accumulators that could not be
+        /// called or could not dispatch the message.
+        /// The number of changed accumulators is therefore less than the number of
+        /// registered accumulators if any of them failed.
+        ///
+        /// The `fee` is charged once. Any transferred value above it is kept by the
+        /// `delegator`, as with `deposit`.
+        #[ink(message, payable)]
+        pub fn broadcast_change(&mut self, by: Value) -> Result<u32> {
+            self.ensure_not_paused()?;
+            self.ensure_allowed()?;
+            self.ensure_delta_allowed(by)?;
+            self.charge_fee(1)?;
+            self.guarded(|delegator| delegator.make_broadcast_change(by))
+        }
+
+        /// Makes the changes for `broadcast_change`.
+        fn make_broadcast_change(&mut self, by: Value) -> Result<u32> {
+            let selector = match self.which {
+                Which::Adder => CHANGE_SELECTOR,
+                Which::Subber => DEC_SELECTOR,
             };
-            let _result = build_call::<<Self as ::ink::env::ContractEnv>::Env>()
-                .call(contract)
-                .call_flags(CallFlags::default())
-                .exec_input(ExecutionInput::new(method_selector.into()).push_arg(by))
-                .returns::<()>()
-                .try_invoke();
+            let mut changed = 0;
+            for &acc_contract in &self.accumulators {
+                let mut accumulator: AccumulatorRef = FromAccountId::from_account_id(acc_contract);
+                let result = match self.which {
+                    Which::Adder => accumulator
+                        .call_mut()
+                        .inc(by)
+                        .gas_limit(self.gas_limit)
+                        .call_flags(self.call_flags())
+                        .try_invoke(),
+                    Which::Subber => accumulator
+                        .call_mut()
+                        .dec(by)
+                        .gas_limit(self.gas_limit)
+                        .call_flags(self.call_flags())
+                        .try_invoke(),
+                };
+                let reason = match result {
+                    Ok(Ok(Ok(()))) => {
+                        changed += 1;
+                        continue;
+                    }
+                    Ok(Ok(Err(_))) => continue,
+                    Ok(Err(_)) => FailureReason::LangError,
+                    Err(_) => FailureReason::EnvError,
+                };
+                self.env().emit_event(DelegationFailed {
+                    target: acc_contract,
+                    selector,
+                    reason,
+                });
+            }
+            self.stats.failed_calls += self.accumulators.len() as u64 - changed as u64;
+            Ok(changed)
         }
 
+        /// Resets the `accumulator` value to zero by calling `reset` on the
+        /// `accumulator` directly.
+        ///
+        /// Returns `DelegatorError::NotConfigured` if the `accumulator` has not been set,
+        /// `DelegatorError::AccumulatorUnreachable` if it could not be called and
+        /// `DelegatorError::AccumulatorRejected` if the `delegator` does not own it.
+        /// Can only be called by an admin. Returns `DelegatorError::ApprovalRequired`
+        /// if approvers are set.
+        ///
+        /// Emits a `Reset` event on success.
         #[ink(message)]
-        pub fn switch(&mut self) {
-            match self.which {
+        pub fn reset(&mut self) -> Result<()> {
+            self.ensure_role(Role::Admin)?;
+            self.ensure_no_threshold()?;
+            self.ensure_not_paused()?;
+            let acc_contract = self.acc_contract();
+            ensure_configured(acc_contract)?;
+            let mut accumulator: AccumulatorRef = FromAccountId::from_account_id(acc_contract);
+            accumulator
+                .call_mut()
+                .reset()
+                .call_flags(self.call_flags())
+                .try_invoke()
+                .map_err(|error| {
+                    DelegatorError::from_env(error, DelegatorError::AccumulatorUnreachable)
+                })?
+                .map_err(DelegatorError::LangError)??;
+            self.base_value = 0;
+            self.env().emit_event(Reset {
+                by: self.env().caller(),
+            });
+            Ok(())
+        }
+
+        /// Delegates the change to the `adder` or `subber`, stores the new
+        /// `accumulator` value they return in `base_value` and emits a `Changed` event.
+        ///
+        /// A `gas_limit` of `0` forwards all remaining gas. The `value` is transferred
+        /// along with the call.
+        ///
+        /// Falls back to the other operator if `fallback_enabled` is set, see
+        /// `delegate_change_with`.
+        fn delegate_change(&mut self, by: Value, gas_limit: u64, value: Balance) -> Result<Value> {
+            self.delegate_change_with(by, gas_limit, value, self.fallback_enabled)
+                .map(|(_, new_value)| new_value)
+        }
+
+        /// Like `delegate_change`, but also returns the operator that made the change.
+        ///
+        /// If `fallback` is set and the call fails, the change is retried once with
+        /// the other operator and the negated delta, which has the same effect on the
+        /// `accumulator`, and a `FellBack` event is emitted. If the retry fails as well,
+        /// the error of the first call is returned. `DelegatorError::DeltaOutOfRange`
+        /// is returned before any call if the delta cannot be negated.
+        ///
+        /// Returns `DelegatorError::Reentrancy` if a change is already in progress,
+        /// see `guarded`.
+        fn delegate_change_with(
+            &mut self,
+            by: Value,
+            gas_limit: u64,
+            value: Balance,
+            fallback: bool,
+        ) -> Result<(Which, Value)> {
+            self.guarded(|delegator| delegator.try_delegate_change(by, gas_limit, value, fallback))
+        }
+
+        /// Runs `change` while marking a change as in progress.
+        ///
+        /// Returns `DelegatorError::Reentrancy` if a change is already in progress,
+        /// which can only happen if `allow_reentry` is set and a called contract calls
+        /// back into the `delegator`. Every message that changes a value has to make
+        /// its change through this.
+        fn guarded<T>(&mut self, change: impl FnOnce(&mut Self) -> Result<T>) -> Result<T> {
+            if self.in_call.get().unwrap_or_default() {
+                return Err(DelegatorError::Reentrancy);
+            }
+            self.in_call.set(&true);
+            let result = change(self);
+            self.in_call.set(&false);
+            result
+        }
+
+        /// Makes the change for `delegate_change_with`.
+        fn try_delegate_change(
+            &mut self,
+            by: Value,
+            gas_limit: u64,
+            value: Balance,
+            fallback: bool,
+        ) -> Result<(Which, Value)> {
+            let negated = if fallback {
+                Some(by.checked_neg().ok_or(DelegatorError::DeltaOutOfRange)?)
+            } else {
+                None
+            };
+            let call_flags = self.call_flags();
+            let error = match self.call_operator(self.which, by, gas_limit, value, call_flags) {
+                Ok(new_value) => {
+                    self.record_change(self.which, by, new_value);
+                    return Ok((self.which, new_value));
+                }
+                Err(error) => error,
+            };
+            let Some(negated) = negated else {
+                return Err(error);
+            };
+            let other = self.which.other();
+            let new_value = self
+                .call_operator(other, negated, gas_limit, value, call_flags)
+                .map_err(|_| error)?;
+            self.env().emit_event(FellBack {
+                from: self.which,
+                to: other,
+            });
+            self.record_change(other, negated, new_value);
+            Ok((other, new_value))
+        }
+
+        /// Stores the `new_value` of the `accumulator` in `base_value`, records the
+        /// change by `by` through `via` as `last_change` and in the contributions, and
+        /// emits a `Changed` event.
+        fn record_change(&mut self, via: Which, by: Value, new_value: Value) {
+            let caller = self.env().caller();
+            self.base_value = new_value;
+            self.last_change = Some((caller, by, self.env().block_number()));
+            let net = match via {
                 Which::Adder => {
-                    self.which = Which::Subber;
+                    self.stats.adder_calls += 1;
+                    by
                 }
                 Which::Subber => {
-                    self.which = Which::Adder;
+                    self.stats.subber_calls += 1;
+                    by.saturating_neg()
                 }
+            };
+            let contribution = self.contribution_of(caller).saturating_add(net);
+            self.contributions.insert(caller, &contribution);
+            self.total_net = self.total_net.saturating_add(net);
+            self.history
+                .insert(self.history_count % HISTORY_LEN, &(via, by));
+            self.history_count = self.history_count.wrapping_add(1);
+            self.env().emit_event(Changed {
+                caller,
+                via,
+                by,
+                new_value,
+            });
+        }
+
+        /// Returns the `accumulator` value as last seen by the `delegator`.
+        ///
+        /// This is the initial value until the first change. It is only updated by
+        /// changes made through the `delegator`, so it can drift from the actual
+        /// `accumulator` value if other contracts change the `accumulator` as well.
+        #[ink(message)]
+        pub fn base_value(&self) -> Value {
+            self.base_value
+        }
+
+        /// Returns the net change of the `accumulator` value made by `account` through
+        /// the `delegator`.
+        ///
+        /// Only successful changes are counted, by their effect: a change by `by`
+        /// counts as `by` through the `adder` and as `-by` through the `subber`. The
+        /// sum saturates at the bounds of a `Value`.
+        #[ink(message)]
+        pub fn contribution_of(&self, account: AccountId) -> Value {
+            self.contributions.get(account).unwrap_or_default()
+        }
+
+        /// Returns the net change of the `accumulator` value made by all callers
+        /// through the `delegator`, counted like `contribution_of`.
+        #[ink(message)]
+        pub fn total_net(&self) -> Value {
+            self.total_net
+        }
+
+        /// Returns the operator and delta of the last `HISTORY_LEN` successful changes
+        /// made through the `delegator`, oldest first.
+        ///
+        /// The history is capped at `HISTORY_LEN` entries, each in its own storage
+        /// cell, so recording a change costs the same regardless of how many changes
+        /// came before it.
+        #[ink(message)]
+        pub fn recent_changes(&self) -> Vec<(Which, Value)> {
+            let len = self.history_count.min(HISTORY_LEN);
+            (self.history_count - len..self.history_count)
+                .filter_map(|n| self.history.get(n % HISTORY_LEN))
+                .collect()
+        }
+
+        /// Returns the caller, delta and block number of the last successful change
+        /// made through the `delegator`, or `None` if there has been none yet.
+        ///
+        /// Failed changes leave the previous record intact.
+        #[ink(message)]
+        pub fn last_change(&self) -> Option<(AccountId, Value, BlockNumber)> {
+            self.last_change
+        }
+
+        /// Delegates the change of the `delegator`'s own value to the code of the
+        /// `adder` or `subber`, depending on the current state.
+        ///
+        /// Unlike `change`, which uses a `Call` to execute the `adder` or `subber` in
+        /// their own context, this uses a `DelegateCall`: the code of the `adder` or
+        /// `subber` is executed in the context of the `delegator`, i.e. against the
+        /// storage and balance of the `delegator` and with the original caller. The
+        /// `accumulator` is not involved; the value can be read with `delegated_value`.
+        ///
+        /// The `fee` is charged like in `change`. Any transferred value above it is
+        /// kept by the `delegator`, as with `deposit`. The delegate call keeps the
+        /// transferred value, so `Adder::inc_delegated` and `Subber::dec_delegated`
+        /// are payable as well.
+        #[ink(message, payable)]
+        pub fn change_delegate(&mut self, by: Value) -> Result<()> {
+            self.ensure_not_paused()?;
+            self.ensure_allowed()?;
+            self.ensure_delta_allowed(by)?;
+            self.charge_fee(1)?;
+            self.guarded(|delegator| delegator.make_change_delegate(by))
+        }
+
+        /// Makes the change for `change_delegate`.
+        fn make_change_delegate(&mut self, by: Value) -> Result<()> {
+            let code_hash = match self.which {
+                Which::Adder => self.add_code_hash,
+                Which::Subber => self.sub_code_hash,
+            };
+            if code_hash == Hash::default() {
+                return Err(DelegatorError::NotConfigured);
+            }
+            let call = build_call::<<Self as ::ink::env::ContractEnv>::Env>()
+                .delegate(code_hash)
+                .call_flags(self.call_flags())
+                .exec_input(
+                    ExecutionInput::new(Selector::new(DELEGATED_CHANGE_SELECTOR)).push_arg(by),
+                );
+            // The delegated messages only fail if the value of the `delegator` would
+            // overflow, the `accumulator` is not involved.
+            match self.which {
+                Which::Adder => call
+                    .returns::<core::result::Result<(), AdderError>>()
+                    .try_invoke()
+                    .map(|result| {
+                        result.map(|result| result.map_err(|_| DelegatorError::Overflow))
+                    }),
+                Which::Subber => call
+                    .returns::<core::result::Result<(), SubberError>>()
+                    .try_invoke()
+                    .map(|result| {
+                        result.map(|result| result.map_err(|_| DelegatorError::Overflow))
+                    }),
             }
+            .map_err(|error| {
+                DelegatorError::from_env(error, DelegatorError::OperatorUnreachable(self.which))
+            })
+            .and_then(|result| result.map_err(DelegatorError::LangError))
+            .and_then(|result| result)
+        }
+
+        /// Returns the value changed by `change_delegate`.
+        #[ink(message)]
+        pub fn delegated_value(&self) -> Value {
+            self.value.get().unwrap_or_default()
+        }
+
+        /// Sets the code hashes of the `adder` and `subber` used by `change_delegate`.
+        ///
+        /// Can only be called by a manager. Returns `DelegatorError::ApprovalRequired`
+        /// if approvers are set, in which case it has to be proposed as
+        /// `Action::SetOperatorHashes`.
+        #[ink(message)]
+        pub fn set_operator_hashes(&mut self, add_hash: Hash, sub_hash: Hash) -> Result<()> {
+            self.ensure_role(Role::Manager)?;
+            self.ensure_no_threshold()?;
+            self.apply_set_operator_hashes(add_hash, sub_hash);
+            Ok(())
+        }
+
+        /// Sets the code hashes of the `adder` and `subber`.
+        fn apply_set_operator_hashes(&mut self, add_hash: Hash, sub_hash: Hash) {
+            self.add_code_hash = add_hash;
+            self.sub_code_hash = sub_hash;
+        }
+
+        /// Returns whether the `delegator` is in `adder` or `subber` state.
+        #[ink(message)]
+        pub fn which(&self) -> Which {
+            self.which
+        }
+
+        /// Returns the `accumulator` smart contract.
+        #[ink(message)]
+        pub fn accumulator(&self) -> AccountId {
+            self.acc_contract()
+        }
+
+        /// Returns the `adder` smart contract.
+        #[ink(message)]
+        pub fn adder(&self) -> AccountId {
+            self.add_contract()
         }
-    }
-}
 
-// /// This is how you'd write end-to-end (E2E) or integration tests for ink! contracts.
-// ///
-// /// When running these you need to make sure that you:
-// /// - Compile the tests with the `e2e-tests` feature flag enabled (`--features e2e-tests`)
-// /// - Are running a Substrate node which contains `pallet-contracts` in the background
-// #[cfg(all(test, feature = "e2e-tests"))]
-// mod e2e_tests {
-//     /// Imports all the definitions from the outer scope so we can use them here.
-//     use super::*;
-
-//     /// A helper function used for calling contract messages.
-//     use ink_e2e::build_message;
-
-//     /// The End-to-End test `Result` type.
-//     type E2EResult<T> = std::result::Result<T, Box<dyn std::error::Error>>;
-
-//     /// We test that we can upload and instantiate the contract using its default constructor.
-//     #[ink_e2e::test]
-//     async fn default_works(mut client: ink_e2e::Client<C, E>) -> E2EResult<()> {
-//         // Given
-//         let constructor = DelegatorRef::default();
-
-//         // When
-//         let contract_account_id = client
-//             .instantiate("delegator", &ink_e2e::alice(), constructor, 0, None)
-//             .await
-//             .expect("instantiate failed")
-//             .account_id;
-
-//         // Then
-//         let get = build_message::<DelegatorRef>(contract_account_id.clone())
-//             .call(|delegator| delegator.get());
-//         let get_result = client.call_dry_run(&ink_e2e::alice(), &get, 0, None).await;
-//         assert!(matches!(get_result.return_value(), false));
-
-//         Ok(())
-//     }
-
-//     /// We test that we can read and write a value from the on-chain contract contract.
-//     #[ink_e2e::test]
-//     async fn it_works(mut client: ink_e2e::Client<C, E>) -> E2EResult<()> {
-//         // Given
-//         let constructor = DelegatorRef::new(false);
-//         let contract_account_id = client
-//             .instantiate("delegator", &ink_e2e::bob(), constructor, 0, None)
-//             .await
-//             .expect("instantiate failed")
-//             .account_id;
-
-//         let get = build_message::<DelegatorRef>(contract_account_id.clone())
-//             .call(|delegator| delegator.get());
-//         let get_result = client.call_dry_run(&ink_e2e::bob(), &get, 0, None).await;
-//         assert!(matches!(get_result.return_value(), false));
-
-//         // When
-//         let flip = build_message::<DelegatorRef>(contract_account_id.clone())
-//             .call(|delegator| delegator.flip());
-//         let _flip_result = client
-//             .call(&ink_e2e::bob(), flip, 0, None)
-//             .await
-//             .expect("flip failed");
-
-//         // Then
-//         let get = build_message::<DelegatorRef>(contract_account_id.clone())
-//             .call(|delegator| delegator.get());
-//         let get_result = client.call_dry_run(&ink_e2e::bob(), &get, 0, None).await;
-//         assert!(matches!(get_result.return_value(), true));
-
-//         Ok(())
-//     }
-// }
+        /// Returns the `subber` smart contract.
+        #[ink(message)]
+        pub fn subber(&self) -> AccountId {
+            self.sub_contract()
+        }
+
+        /// Returns how the `delegator` has been used.
+        ///
+        /// Only changes made through the `delegator` that succeeded are counted per
+        /// operator. Failed calls are only counted if they do not revert the message,
+        /// which is the case for a `LangError` returned by `try_change` and for the
+        /// accumulators skipped by `broadcast_change`: any other failure returns an
+        /// error, which reverts the counter along with the rest of the storage.
+        #[ink(message)]
+        pub fn stats(&self) -> Stats {
+            self.stats
+        }
+
+        /// Returns the number of successful changes routed to the `adder` and to the
+        /// `subber`, as counted in `stats`.
+        #[ink(message)]
+        pub fn call_stats(&self) -> (u64, u64) {
+            (self.stats.adder_calls, self.stats.subber_calls)
+        }
+
+        /// Returns all contracts the `delegator` delegates to at once.
+        #[ink(message)]
+        pub fn targets(&self) -> Targets {
+            Targets {
+                acc: self.acc_contract(),
+                add: self.add_contract(),
+                sub: self.sub_contract(),
+            }
+        }
+
+        /// Sets the `accumulator` smart contract.
+        ///
+        /// Returns `DelegatorError::NotAContract` if `addr` is not a contract.
+        /// Can only be called by a manager. Returns `DelegatorError::ApprovalRequired`
+        /// if approvers are set, in which case it has to be proposed as
+        /// `Action::SetAccumulator`.
+        #[ink(message)]
+        pub fn set_accumulator(&mut self, addr: AccountId) -> Result<()> {
+            self.ensure_role(Role::Manager)?;
+            self.ensure_no_threshold()?;
+            self.apply_set_accumulator(addr)
+        }
+
+        /// Sets the `accumulator` smart contract and emits a `TargetUpdated` event
+        /// unless `addr` is not a contract.
+        fn apply_set_accumulator(&mut self, addr: AccountId) -> Result<()> {
+            ensure_contract(addr)?;
+            let old = self.acc_contract();
+            self.acc_contract.set(&addr);
+            self.env().emit_event(TargetUpdated {
+                which_target: Target::Accumulator,
+                old,
+                new: addr,
+            });
+            Ok(())
+        }
+
+        /// Moves the current `accumulator` value to `new_acc` and makes it the
+        /// `accumulator`.
+        ///
+        /// Resets `new_acc`, increases it by the value of the current `accumulator` and
+        /// points the `adder` and `subber` to it with their `set_accumulator`. The
+        /// `accumulator` is only replaced once all calls succeeded, otherwise an error
+        /// is returned and nothing changes.
+        ///
+        /// Returns `DelegatorError::NotAContract` if `new_acc` is not a contract,
+        /// `DelegatorError::AccumulatorUnreachable` if either `accumulator` could not
+        /// be called and `DelegatorError::AccumulatorRejected` if the `delegator` does
+        /// not own `new_acc`, which it resets. Returns
+        /// `DelegatorError::OperatorUnreachable` if the `adder` or `subber` could not be
+        /// called and `DelegatorError::Unauthorized` if the `delegator` does not own
+        /// them. Can only be called by an admin. Returns
+        /// `DelegatorError::ApprovalRequired` if approvers are set, in which case it
+        /// has to be proposed as `Action::MigrateAccumulator`.
+        ///
+        /// Emits an `AccumulatorMigrated` event on success.
+        #[ink(message)]
+        pub fn migrate_accumulator(&mut self, new_acc: AccountId) -> Result<()> {
+            self.ensure_role(Role::Admin)?;
+            self.ensure_no_threshold()?;
+            self.apply_migrate_accumulator(new_acc)
+        }
+
+        /// Moves the current `accumulator` value to `new_acc`, makes it the
+        /// `accumulator` and emits an `AccumulatorMigrated` event.
+        fn apply_migrate_accumulator(&mut self, new_acc: AccountId) -> Result<()> {
+            ensure_contract(new_acc)?;
+            let old = self.acc_contract();
+            let value = self.read_accumulator(self.call_flags())?;
+            let mut accumulator: AccumulatorRef = FromAccountId::from_account_id(new_acc);
+            accumulator
+                .call_mut()
+                .reset()
+                .call_flags(self.call_flags())
+                .try_invoke()
+                .map_err(|error| {
+                    DelegatorError::from_env(error, DelegatorError::AccumulatorUnreachable)
+                })?
+                .map_err(DelegatorError::LangError)??;
+            accumulator
+                .call_mut()
+                .inc(value)
+                .call_flags(self.call_flags())
+                .try_invoke()
+                .map_err(|error| {
+                    DelegatorError::from_env(error, DelegatorError::AccumulatorUnreachable)
+                })?
+                .map_err(DelegatorError::LangError)??;
+            self.repoint_operators(new_acc)?;
+            self.acc_contract.set(&new_acc);
+            self.env().emit_event(AccumulatorMigrated {
+                old,
+                new: new_acc,
+                value,
+            });
+            Ok(())
+        }
+
+        /// Points the `adder` and `subber` to `new_acc`.
+        fn repoint_operators(&self, new_acc: AccountId) -> Result<()> {
+            let mut adder: AdderRef = FromAccountId::from_account_id(self.add_contract());
+            adder
+                .call_mut()
+                .set_accumulator(new_acc)
+                .call_flags(self.call_flags())
+                .try_invoke()
+                .map_err(|error| {
+                    DelegatorError::from_env(
+                        error,
+                        DelegatorError::OperatorUnreachable(Which::Adder),
+                    )
+                })?
+                .map_err(DelegatorError::LangError)??;
+            let mut subber: SubberRef = FromAccountId::from_account_id(self.sub_contract());
+            subber
+                .call_mut()
+                .set_accumulator(new_acc)
+                .call_flags(self.call_flags())
+                .try_invoke()
+                .map_err(|error| {
+                    DelegatorError::from_env(
+                        error,
+                        DelegatorError::OperatorUnreachable(Which::Subber),
+                    )
+                })?
+                .map_err(DelegatorError::LangError)??;
+            Ok(())
+        }
+
+        /// Sets the `adder` smart contract.
+        ///
+        /// Returns `DelegatorError::NotAContract` if `addr` is not a contract.
+        /// Can only be called by a manager. Returns `DelegatorError::ApprovalRequired`
+        /// if approvers are set, in which case it has to be proposed as
+        /// `Action::SetAdder`.
+        #[ink(message)]
+        pub fn set_adder(&mut self, addr: AccountId) -> Result<()> {
+            self.ensure_role(Role::Manager)?;
+            self.ensure_no_threshold()?;
+            self.apply_set_adder(addr)
+        }
+
+        /// Sets the `subber` smart contract.
+        ///
+        /// Returns `DelegatorError::NotAContract` if `addr` is not a contract.
+        /// Can only be called by a manager. Returns `DelegatorError::ApprovalRequired`
+        /// if approvers are set, in which case it has to be proposed as
+        /// `Action::SetSubber`.
+        #[ink(message)]
+        pub fn set_subber(&mut self, addr: AccountId) -> Result<()> {
+            self.ensure_role(Role::Manager)?;
+            self.ensure_no_threshold()?;
+            self.apply_set_subber(addr)
+        }
+
+        /// Sets the `subber` smart contract and emits a `TargetUpdated` event unless
+        /// `addr` is not a contract.
+        fn apply_set_subber(&mut self, addr: AccountId) -> Result<()> {
+            ensure_contract(addr)?;
+            let old = self.sub_contract();
+            self.sub_contract.set(&addr);
+            self.env().emit_event(TargetUpdated {
+                which_target: Target::Subber,
+                old,
+                new: addr,
+            });
+            Ok(())
+        }
+
+        /// Registers an additional `accumulator` to be changed by `broadcast_change`.
+        ///
+        /// Returns `DelegatorError::InvalidTarget` for the zero address,
+        /// `DelegatorError::DuplicateTarget` if it is already registered and
+        /// `DelegatorError::BatchTooLarge` if `MAX_BATCH` accumulators are registered.
+        /// Can only be called by a manager. Returns `DelegatorError::ApprovalRequired`
+        /// if approvers are set.
+        #[ink(message)]
+        pub fn add_accumulator(&mut self, addr: AccountId) -> Result<()> {
+            self.ensure_role(Role::Manager)?;
+            self.ensure_no_threshold()?;
+            if addr == unconfigured() {
+                return Err(DelegatorError::InvalidTarget);
+            }
+            if self.accumulators.contains(&addr) {
+                return Err(DelegatorError::DuplicateTarget);
+            }
+            if self.accumulators.len() >= MAX_BATCH {
+                return Err(DelegatorError::BatchTooLarge);
+            }
+            self.accumulators.push(addr);
+            Ok(())
+        }
+
+        /// Unregisters an `accumulator` registered with `add_accumulator`.
+        ///
+        /// Returns `DelegatorError::InvalidTarget` if it is not registered.
+        /// Can only be called by a manager. Returns `DelegatorError::ApprovalRequired`
+        /// if approvers are set.
+        #[ink(message)]
+        pub fn remove_accumulator(&mut self, addr: AccountId) -> Result<()> {
+            self.ensure_role(Role::Manager)?;
+            self.ensure_no_threshold()?;
+            let index = self
+                .accumulators
+                .iter()
+                .position(|acc_contract| *acc_contract == addr)
+                .ok_or(DelegatorError::InvalidTarget)?;
+            self.accumulators.remove(index);
+            Ok(())
+        }
+
+        /// Returns the accumulators registered with `add_accumulator`.
+        #[ink(message)]
+        pub fn accumulators(&self) -> Vec<AccountId> {
+            self.accumulators.clone()
+        }
+
+        /// Returns the version of the code the `delegator` is running, which changes
+        /// with `upgrade`, and the version of its storage layout, which changes with
+        /// `migrate`.
+        #[ink(message)]
+        pub fn version(&self) -> (u32, u32) {
+            (CONTRACT_VERSION, self.storage_version)
+        }
+
+        /// Returns the account allowed to reconfigure the `delegator`.
+        #[ink(message)]
+        pub fn owner(&self) -> AccountId {
+            self.owner
+        }
+
+        /// Returns the account proposed to become the new owner, if any.
+        #[ink(message)]
+        pub fn pending_owner(&self) -> Option<AccountId> {
+            self.pending_owner
+        }
+
+        /// Proposes `new_owner` as the new owner, who then has to accept the ownership.
+        ///
+        /// Replaces any previous proposal. Can only be called by the owner.
+        #[ink(message)]
+        pub fn propose_owner(&mut self, new_owner: AccountId) -> Result<()> {
+            self.ensure_owner()?;
+            self.pending_owner = Some(new_owner);
+            Ok(())
+        }
+
+        /// Accepts the ownership of the `delegator`.
+        ///
+        /// All roles are revoked from the previous owner and granted to the new owner.
+        /// Can only be called by the proposed owner.
+        #[ink(message)]
+        pub fn accept_ownership(&mut self) -> Result<()> {
+            let caller = self.env().caller();
+            if self.pending_owner != Some(caller) {
+                return Err(DelegatorError::Unauthorized);
+            }
+            let old = core::mem::replace(&mut self.owner, caller);
+            self.pending_owner = None;
+            for role in [Role::Admin, Role::Manager, Role::Pauser] {
+                self.revoke(old, role);
+                self.grant(caller, role);
+            }
+            self.env()
+                .emit_event(OwnershipTransferred { old, new: caller });
+            Ok(())
+        }
+
+        /// Returns whether `account` holds `role`.
+        #[ink(message)]
+        pub fn has_role(&self, account: AccountId, role: Role) -> bool {
+            self.roles.contains((account, role))
+        }
+
+        /// Grants `role` to `account`.
+        ///
+        /// Can only be called by an admin. Returns `DelegatorError::ApprovalRequired`
+        /// if approvers are set, in which case it has to be proposed as
+        /// `Action::GrantRole`.
+        #[ink(message)]
+        pub fn grant_role(&mut self, account: AccountId, role: Role) -> Result<()> {
+            self.ensure_role(Role::Admin)?;
+            self.ensure_no_threshold()?;
+            self.grant(account, role);
+            Ok(())
+        }
+
+        /// Revokes `role` from `account`.
+        ///
+        /// Can only be called by an admin.
+        #[ink(message)]
+        pub fn revoke_role(&mut self, account: AccountId, role: Role) -> Result<()> {
+            self.ensure_role(Role::Admin)?;
+            self.revoke(account, role);
+            Ok(())
+        }
+
+        /// Grants `role` to `account` and emits a `RoleGranted` event if it did not
+        /// hold it yet.
+        fn grant(&mut self, account: AccountId, role: Role) {
+            if self.roles.insert((account, role), &()).is_none() {
+                self.env().emit_event(RoleGranted {
+                    account,
+                    role,
+                    by: self.env().caller(),
+                });
+            }
+        }
+
+        /// Revokes `role` from `account` and emits a `RoleRevoked` event if it held it.
+        fn revoke(&mut self, account: AccountId, role: Role) {
+            if self.roles.take((account, role)).is_some() {
+                self.env().emit_event(RoleRevoked {
+                    account,
+                    role,
+                    by: self.env().caller(),
+                });
+            }
+        }
+
+        /// Cancels the pending ownership proposal, if any.
+        ///
+        /// Can only be called by the owner.
+        #[ink(message)]
+        pub fn cancel_pending_owner(&mut self) -> Result<()> {
+            self.ensure_owner()?;
+            self.pending_owner = None;
+            Ok(())
+        }
+
+        /// Returns the gas limit for delegated calls.
+        #[ink(message)]
+        pub fn gas_limit(&self) -> u64 {
+            self.gas_limit
+        }
+
+        /// Sets the gas limit for delegated calls, where `0` forwards all remaining gas.
+        ///
+        /// Can only be called by an admin. Returns `DelegatorError::ApprovalRequired`
+        /// if approvers are set, in which case it has to be proposed as
+        /// `Action::SetGasLimit`.
+        #[ink(message)]
+        pub fn set_gas_limit(&mut self, limit: u64) -> Result<()> {
+            self.ensure_role(Role::Admin)?;
+            self.ensure_no_threshold()?;
+            self.gas_limit = limit;
+            Ok(())
+        }
+
+        /// Returns the largest absolute delta accepted, where `0` accepts any delta.
+        #[ink(message)]
+        pub fn max_delta(&self) -> u32 {
+            self.max_delta
+        }
+
+        /// Sets the largest absolute delta accepted, where `0` accepts any delta.
+        ///
+        /// Can only be called by an admin.
+        ///
+        /// Emits a `MaxDeltaChanged` event.
+        #[ink(message)]
+        pub fn set_max_delta(&mut self, max_delta: u32) -> Result<()> {
+            self.ensure_role(Role::Admin)?;
+            self.max_delta = max_delta;
+            self.env().emit_event(MaxDeltaChanged { max_delta });
+            Ok(())
+        }
+
+        /// Returns whether called contracts may call back into the `delegator`.
+        #[ink(message)]
+        pub fn allow_reentry(&self) -> bool {
+            self.allow_reentry
+        }
+
+        /// Sets whether called contracts may call back into the `delegator`.
+        ///
+        /// # Note
+        ///
+        /// Enabling reentry opens up a reentrancy surface: the `accumulator`, `adder`
+        /// and `subber` are able to call messages of the `delegator` while a call
+        /// to them is still in progress.
+        ///
+        /// Can only be called by an admin. Returns `DelegatorError::ApprovalRequired`
+        /// if approvers are set, in which case it has to be proposed as
+        /// `Action::SetAllowReentry`.
+        #[ink(message)]
+        pub fn set_allow_reentry(&mut self, allow: bool) -> Result<()> {
+            self.ensure_role(Role::Admin)?;
+            self.ensure_no_threshold()?;
+            self.apply_allow_reentry(allow);
+            Ok(())
+        }
+
+        /// Sets whether called contracts may call back into the `delegator` and emits
+        /// a `ReentryPolicyChanged` event.
+        fn apply_allow_reentry(&mut self, allow: bool) {
+            self.allow_reentry = allow;
+            self.env().emit_event(ReentryPolicyChanged { allow });
+        }
+
+        /// Returns whether changes fall back to the other operator if the call to the
+        /// operator in use fails.
+        #[ink(message)]
+        pub fn fallback_enabled(&self) -> bool {
+            self.fallback_enabled
+        }
+
+        /// Sets whether changes fall back to the other operator if the call to the
+        /// operator in use fails, see `change`.
+        ///
+        /// Can only be called by an admin.
+        #[ink(message)]
+        pub fn set_fallback_enabled(&mut self, enabled: bool) -> Result<()> {
+            self.ensure_role(Role::Admin)?;
+            self.fallback_enabled = enabled;
+            Ok(())
+        }
+
+        /// Returns whether only accounts on the allowlist may change the value.
+        #[ink(message)]
+        pub fn allowlist_enabled(&self) -> bool {
+            self.allowlist_enabled
+        }
+
+        /// Enables or disables the allowlist.
+        ///
+        /// While enabled, the messages changing the value return
+        /// `DelegatorError::NotAllowed` for callers that are not on the allowlist,
+        /// before any other contract is called. Read-only messages stay open to
+        /// everyone. Can only be called by an admin.
+        #[ink(message)]
+        pub fn set_allowlist_enabled(&mut self, enabled: bool) -> Result<()> {
+            self.ensure_role(Role::Admin)?;
+            self.allowlist_enabled = enabled;
+            Ok(())
+        }
+
+        /// Returns whether `account` is on the allowlist.
+        #[ink(message)]
+        pub fn is_allowed(&self, account: AccountId) -> bool {
+            self.allowed.contains(account)
+        }
+
+        /// Adds `account` to the allowlist.
+        ///
+        /// Can only be called by an admin.
+        #[ink(message)]
+        pub fn allow(&mut self, account: AccountId) -> Result<()> {
+            self.ensure_role(Role::Admin)?;
+            self.allowed.insert(account, &());
+            Ok(())
+        }
+
+        /// Removes `account` from the allowlist.
+        ///
+        /// Can only be called by an admin.
+        #[ink(message)]
+        pub fn disallow(&mut self, account: AccountId) -> Result<()> {
+            self.ensure_role(Role::Admin)?;
+            self.allowed.remove(account);
+            Ok(())
+        }
+
+        /// Returns whether changes and switches are blocked.
+        #[ink(message)]
+        pub fn paused(&self) -> bool {
+            self.paused
+        }
+
+        /// Blocks or unblocks changes and switches, e.g. in case of an emergency.
+        ///
+        /// Read-only messages keep working while paused. Can only be called by a pauser.
+        /// Returns `DelegatorError::ApprovalRequired` when pausing while approvers are
+        /// set, in which case it has to be proposed as `Action::Pause`.
+        #[ink(message)]
+        pub fn set_paused(&mut self, paused: bool) -> Result<()> {
+            self.ensure_role(Role::Pauser)?;
+            if paused {
+                self.ensure_no_threshold()?;
+            }
+            self.apply_paused(paused);
+            Ok(())
+        }
+
+        /// Blocks or unblocks changes and switches and emits `Paused` or `Unpaused`
+        /// if that changed anything.
+        fn apply_paused(&mut self, paused: bool) {
+            if self.paused != paused {
+                self.paused = paused;
+                let by = self.env().caller();
+                if paused {
+                    self.env().emit_event(Paused { by });
+                } else {
+                    self.env().emit_event(Unpaused { by });
+                }
+            }
+        }
+
+        /// Blocks changes and switches, see `set_paused`.
+        #[ink(message)]
+        pub fn pause(&mut self) -> Result<()> {
+            self.set_paused(true)
+        }
+
+        /// Unblocks changes and switches, see `set_paused`.
+        #[ink(message)]
+        pub fn unpause(&mut self) -> Result<()> {
+            self.set_paused(false)
+        }
+
+        /// Returns the number of blocks that have to pass between two switches.
+        #[ink(message)]
+        pub fn switch_cooldown(&self) -> BlockNumber {
+            self.switch_cooldown
+        }
+
+        /// Sets the number of blocks that have to pass between two switches.
+        ///
+        /// Can only be called by an admin.
+        #[ink(message)]
+        pub fn set_switch_cooldown(&mut self, cooldown: BlockNumber) -> Result<()> {
+            self.ensure_role(Role::Admin)?;
+            self.switch_cooldown = cooldown;
+            Ok(())
+        }
+
+        /// Switches the `delegator` between `adder` and `subber` state.
+        ///
+        /// Can only be called by an admin.
+        #[ink(message)]
+        pub fn switch(&mut self) -> Result<()> {
+            self.switch_to(self.which.other())
+        }
+
+        /// Switches the `delegator` to the `target` state.
+        ///
+        /// Does nothing if the `delegator` is already in the `target` state, so it can
+        /// be used to express the intended state regardless of the current one.
+        ///
+        /// Returns `DelegatorError::CooldownActive` if the last switch was less than
+        /// `switch_cooldown` blocks ago and `DelegatorError::TimelockRequired` if a
+        /// `switch_delay` is set, in which case the switch has to be scheduled with
+        /// `schedule_switch`. Can only be called by an admin.
+        #[ink(message)]
+        pub fn switch_to(&mut self, target: Which) -> Result<()> {
+            self.ensure_role(Role::Admin)?;
+            if self.switch_delay > 0 {
+                return Err(DelegatorError::TimelockRequired);
+            }
+            self.apply_switch(target)
+        }
+
+        /// Switches the `delegator` to the `target` state like `switch_to` and then
+        /// changes the `accumulator` value like `change`, returning the new value.
+        ///
+        /// If the change fails, its error is returned and the switch is undone as
+        /// well: `which` is restored, and returning the error reverts everything else
+        /// the switch recorded. Can only be called by an admin.
+        #[ink(message, payable)]
+        pub fn switch_and_change(&mut self, target: Which, by: Value) -> Result<Value> {
+            let which = self.which;
+            self.switch_to(target)?;
+            self.change(by).map_err(|error| {
+                self.which = which;
+                error
+            })
+        }
+
+        /// Returns up to `count` of the most recent switches, newest first.
+        ///
+        /// Only the last `SWITCH_LOG_CAPACITY` switches are kept, so fewer are returned
+        /// if `count` exceeds that.
+        #[ink(message)]
+        pub fn switch_history(&self, count: u32) -> Vec<SwitchRecord> {
+            let len = count.min(self.switch_log_head).min(SWITCH_LOG_CAPACITY);
+            (1..=len)
+                .filter_map(|i| {
+                    self.switch_log
+                        .get((self.switch_log_head - i) % SWITCH_LOG_CAPACITY)
+                })
+                .collect()
+        }
+
+        /// Returns the number of blocks between scheduling a switch and being able to
+        /// execute it, where `0` allows switching directly.
+        #[ink(message)]
+        pub fn switch_delay(&self) -> BlockNumber {
+            self.switch_delay
+        }
+
+        /// Sets the number of blocks between scheduling a switch and being able to
+        /// execute it, where `0` allows switching directly.
+        ///
+        /// A switch that has already been scheduled keeps its block number. Can only
+        /// be called by an admin.
+        #[ink(message)]
+        pub fn set_switch_delay(&mut self, delay: BlockNumber) -> Result<()> {
+            self.ensure_role(Role::Admin)?;
+            self.switch_delay = delay;
+            Ok(())
+        }
+
+        /// Returns the scheduled switch and the block number from which it can be
+        /// executed, if any.
+        #[ink(message)]
+        pub fn pending_switch(&self) -> Option<(Which, BlockNumber)> {
+            self.pending_switch
+        }
+
+        /// Schedules a switch to the `target` state that can be executed with
+        /// `execute_switch` once `switch_delay` blocks have passed.
+        ///
+        /// Replaces any previously scheduled switch, starting a fresh delay.
+        /// Can only be called by an admin.
+        #[ink(message)]
+        pub fn schedule_switch(&mut self, target: Which) -> Result<()> {
+            self.ensure_role(Role::Admin)?;
+            let executable_at = self
+                .env()
+                .block_number()
+                .checked_add(self.switch_delay)
+                .ok_or(DelegatorError::Overflow)?;
+            self.pending_switch = Some((target, executable_at));
+            self.env().emit_event(SwitchScheduled {
+                target,
+                executable_at,
+            });
+            Ok(())
+        }
+
+        /// Executes the scheduled switch.
+        ///
+        /// Returns `DelegatorError::NoPendingSwitch` if no switch has been scheduled
+        /// and `DelegatorError::TimelockNotExpired` if its delay has not passed yet.
+        /// Otherwise fails like `switch_to`. Can be called by anyone.
+        #[ink(message)]
+        pub fn execute_switch(&mut self) -> Result<()> {
+            let (target, executable_at) =
+                self.pending_switch.ok_or(DelegatorError::NoPendingSwitch)?;
+            if self.env().block_number() < executable_at {
+                return Err(DelegatorError::TimelockNotExpired);
+            }
+            self.pending_switch = None;
+            self.apply_switch(target)?;
+            self.env().emit_event(SwitchExecuted { target });
+            Ok(())
+        }
+
+        /// Cancels the scheduled switch.
+        ///
+        /// Returns `DelegatorError::NoPendingSwitch` if no switch has been scheduled.
+        /// Can only be called by an admin.
+        #[ink(message)]
+        pub fn cancel_switch(&mut self) -> Result<()> {
+            self.ensure_role(Role::Admin)?;
+            let (target, _) = self
+                .pending_switch
+                .take()
+                .ok_or(DelegatorError::NoPendingSwitch)?;
+            self.env().emit_event(SwitchCancelled { target });
+            Ok(())
+        }
+
+        /// Switches the `delegator` to the `target` state unless it is paused or the
+        /// last switch was too recent, and emits a `Switched` event.
+        fn apply_switch(&mut self, target: Which) -> Result<()> {
+            self.ensure_not_paused()?;
+            if self.which == target {
+                return Ok(());
+            }
+            let block_number = self.env().block_number();
+            let elapsed = block_number.saturating_sub(self.last_switch_block);
+            if elapsed < self.switch_cooldown {
+                return Err(DelegatorError::CooldownActive {
+                    remaining: self.switch_cooldown - elapsed,
+                });
+            }
+            self.last_switch_block = block_number;
+            let from = core::mem::replace(&mut self.which, target);
+            self.stats.switch_count += 1;
+            let by = self.env().caller();
+            self.switch_log.insert(
+                self.switch_log_head % SWITCH_LOG_CAPACITY,
+                &SwitchRecord {
+                    to: target,
+                    by,
+                    at: block_number,
+                },
+            );
+            self.switch_log_head = self.switch_log_head.wrapping_add(1);
+            self.env().emit_event(Switched {
+                from,
+                to: target,
+                by,
+            });
+            Ok(())
+        }
+
+        /// Removes the `delegator` and transfers its remaining balance to `beneficiary`.
+        ///
+        /// Returns `DelegatorError::SwitchPending` while a switch is scheduled, so that
+        /// an announced switch cannot be cut short. Can only be called by an admin.
+        /// Returns `DelegatorError::ApprovalRequired` if approvers are set, in which
+        /// case it has to be proposed as `Action::Terminate`.
+        #[ink(message)]
+        pub fn terminate(&mut self, beneficiary: AccountId) -> Result<()> {
+            self.ensure_role(Role::Admin)?;
+            self.ensure_no_threshold()?;
+            self.apply_terminate(beneficiary)
+        }
+
+        /// Removes the `delegator` in favour of `beneficiary` unless a switch is
+        /// scheduled.
+        fn apply_terminate(&mut self, beneficiary: AccountId) -> Result<()> {
+            if self.pending_switch.is_some() {
+                return Err(DelegatorError::SwitchPending);
+            }
+            self.env().terminate_contract(beneficiary)
+        }
+
+        /// Returns the balance held by the `delegator`.
+        #[ink(message)]
+        pub fn balance(&self) -> Balance {
+            self.env().balance()
+        }
+
+        /// Funds the `delegator` with the transferred value.
+        #[ink(message, payable)]
+        pub fn deposit(&mut self) {}
+
+        /// Transfers `amount` out of the balance of the `delegator` to `to`.
+        ///
+        /// The collected fees cannot be withdrawn this way, see `withdraw_fees`.
+        /// Returns `DelegatorError::WithdrawFailed` if `amount` exceeds the balance
+        /// without the collected fees or the transfer fails. Can only be called by an
+        /// admin. Returns `DelegatorError::ApprovalRequired` if approvers
+        /// are set, in which case it has to be proposed as `Action::Withdraw`.
+        #[ink(message)]
+        pub fn withdraw(&mut self, amount: Balance, to: AccountId) -> Result<()> {
+            self.ensure_role(Role::Admin)?;
+            self.ensure_no_threshold()?;
+            self.apply_withdraw(amount, to)
+        }
+
+        /// Transfers `amount` out of the balance of the `delegator` to `to`, leaving the
+        /// collected fees.
+        fn apply_withdraw(&mut self, amount: Balance, to: AccountId) -> Result<()> {
+            if amount > self.env().balance().saturating_sub(self.collected_fees) {
+                return Err(DelegatorError::WithdrawFailed);
+            }
+            self.env()
+                .transfer(to, amount)
+                .map_err(|_| DelegatorError::WithdrawFailed)
+        }
+
+        /// Returns the fee charged for each change, see `change`.
+        #[ink(message)]
+        pub fn fee(&self) -> Balance {
+            self.fee
+        }
+
+        /// Sets the fee charged for each change, where `0` charges nothing.
+        ///
+        /// Can only be called by an admin. Returns `DelegatorError::ApprovalRequired`
+        /// if approvers are set, in which case it has to be proposed as
+        /// `Action::SetFee`.
+        #[ink(message)]
+        pub fn set_fee(&mut self, fee: Balance) -> Result<()> {
+            self.ensure_role(Role::Admin)?;
+            self.ensure_no_threshold()?;
+            self.fee = fee;
+            Ok(())
+        }
+
+        /// Returns the fees charged since they were last withdrawn.
+        #[ink(message)]
+        pub fn collected_fees(&self) -> Balance {
+            self.collected_fees
+        }
+
+        /// Transfers the fees charged since they were last withdrawn to `to`.
+        ///
+        /// Returns `DelegatorError::WithdrawFailed` if the transfer fails. Can only be
+        /// called by an admin. Returns `DelegatorError::ApprovalRequired` if
+        /// approvers are set, in which case it has to be proposed as
+        /// `Action::WithdrawFees`.
+        #[ink(message)]
+        pub fn withdraw_fees(&mut self, to: AccountId) -> Result<()> {
+            self.ensure_role(Role::Admin)?;
+            self.ensure_no_threshold()?;
+            self.apply_withdraw_fees(to)
+        }
+
+        /// Transfers the collected fees to `to` and clears them.
+        fn apply_withdraw_fees(&mut self, to: AccountId) -> Result<()> {
+            self.env()
+                .transfer(to, self.collected_fees)
+                .map_err(|_| DelegatorError::WithdrawFailed)?;
+            self.collected_fees = 0;
+            Ok(())
+        }
+
+        /// Calls the message with the given `selector` on `target`, with `input` as
+        /// the already encoded arguments, and returns the encoded return value.
+        ///
+        /// Allows calling messages the `delegator` does not know about, with the
+        /// `delegator` as the caller. The `input` is passed on as is, without a length
+        /// prefix. Returns `DelegatorError::TargetUnreachable` if `target` cannot be
+        /// called, `DelegatorError::TargetReverted` if it reverted and
+        /// `DelegatorError::LangError` if it failed to dispatch the message. Can only
+        /// be called by an admin and not at all once approvers are set, as there is
+        /// no `Action` for arbitrary calls.
+        #[ink(message)]
+        pub fn raw_call(
+            &mut self,
+            target: AccountId,
+            selector: [u8; 4],
+            input: Vec<u8>,
+        ) -> Result<Vec<u8>> {
+            self.ensure_role(Role::Admin)?;
+            self.ensure_no_threshold()?;
+            let RawOutput(output) = build_call::<<Self as ::ink::env::ContractEnv>::Env>()
+                .call(target)
+                .call_flags(self.call_flags())
+                .exec_input(ExecutionInput::new(Selector::new(selector)).push_arg(RawInput(&input)))
+                .returns::<RawOutput>()
+                .try_invoke()
+                .map_err(|error| {
+                    DelegatorError::from_env(error, DelegatorError::TargetUnreachable)
+                })?
+                .map_err(DelegatorError::LangError)?;
+            Ok(output)
+        }
+
+        /// Handles calls with a selector none of the other messages has.
+        ///
+        /// Instead of failing to dispatch, the call emits an `UnknownSelector` event.
+        /// It returns `Ok(())` rather than an error because ink! reverts a message
+        /// returning an `Err`, which would also discard the event.
+        ///
+        /// This is also where unknown calls would be forwarded to the operator in use
+        /// if the `delegator` ever acts as a proxy.
+        #[ink(message, selector = _)]
+        pub fn fallback(&self) -> Result<()> {
+            self.env().emit_event(UnknownSelector {
+                caller: self.env().caller(),
+            });
+            Ok(())
+        }
+
+        /// Replaces the code of the `delegator` with the code behind `code_hash`.
+        ///
+        /// The storage is kept as is, so the new code has to be compatible with the
+        /// current storage layout. Can only be called by an admin. Returns
+        /// `DelegatorError::ApprovalRequired` if approvers are set, in which case it
+        /// has to be proposed as `Action::Upgrade`.
+        #[ink(message)]
+        pub fn upgrade(&mut self, code_hash: Hash) -> Result<()> {
+            self.ensure_role(Role::Admin)?;
+            self.ensure_no_threshold()?;
+            self.apply_upgrade(code_hash)
+        }
+
+        /// Migrates the storage to the layout of the current code after an `upgrade`.
+        ///
+        /// Returns `DelegatorError::AlreadyMigrated` if the storage is already at
+        /// `STORAGE_VERSION`. Can only be called by an admin.
+        #[ink(message)]
+        pub fn migrate(&mut self) -> Result<()> {
+            self.ensure_role(Role::Admin)?;
+            if self.storage_version >= STORAGE_VERSION {
+                return Err(DelegatorError::AlreadyMigrated);
+            }
+            self.storage_version = STORAGE_VERSION;
+            Ok(())
+        }
+
+        /// Replaces the code of the `delegator` and emits an `Upgraded` event.
+        fn apply_upgrade(&mut self, code_hash: Hash) -> Result<()> {
+            let old_hash = self
+                .env()
+                .own_code_hash()
+                .map_err(|_| DelegatorError::UpgradeFailed)?;
+            self.env()
+                .set_code_hash(&code_hash)
+                .map_err(|_| DelegatorError::UpgradeFailed)?;
+            self.env().emit_event(Upgraded {
+                old_hash,
+                new_hash: code_hash,
+            });
+            Ok(())
+        }
+
+        /// Proposes an admin `action` and returns the id of the proposal.
+        ///
+        /// The proposal counts as approved by the caller. Once `threshold` approvers
+        /// approved it with `approve`, anyone can apply it with `execute`.
+        /// Can only be called by an approver.
+        #[ink(message)]
+        pub fn propose(&mut self, action: Action) -> Result<u32> {
+            let caller = self.env().caller();
+            self.ensure_approver(caller)?;
+            let id = self.next_proposal_id;
+            self.next_proposal_id = id.checked_add(1).ok_or(DelegatorError::Overflow)?;
+            let proposal = Proposal {
+                action,
+                approvals: ink::prelude::vec![caller],
+            };
+            self.proposals.insert(id, &proposal);
+            Ok(id)
+        }
+
+        /// Approves the pending proposal `id`.
+        ///
+        /// Returns `DelegatorError::UnknownProposal` if there is no such proposal and
+        /// `DelegatorError::AlreadyApproved` if the caller approved it before.
+        /// Can only be called by an approver.
+        #[ink(message)]
+        pub fn approve(&mut self, id: u32) -> Result<()> {
+            let caller = self.env().caller();
+            self.ensure_approver(caller)?;
+            let mut proposal = self
+                .proposals
+                .get(id)
+                .ok_or(DelegatorError::UnknownProposal)?;
+            if proposal.approvals.contains(&caller) {
+                return Err(DelegatorError::AlreadyApproved);
+            }
+            proposal.approvals.push(caller);
+            self.proposals.insert(id, &proposal);
+            Ok(())
+        }
+
+        /// Applies the pending proposal `id` and removes it.
+        ///
+        /// Returns `DelegatorError::UnknownProposal` if there is no such proposal and
+        /// `DelegatorError::ThresholdNotReached` if it has fewer than `threshold`
+        /// approvals. Can be called by anyone.
+        #[ink(message)]
+        pub fn execute(&mut self, id: u32) -> Result<()> {
+            let proposal = self
+                .proposals
+                .get(id)
+                .ok_or(DelegatorError::UnknownProposal)?;
+            if proposal.approvals.len() < self.threshold as usize {
+                return Err(DelegatorError::ThresholdNotReached);
+            }
+            self.proposals.remove(id);
+            match proposal.action {
+                Action::SetAdder(addr) => self.apply_set_adder(addr)?,
+                Action::Upgrade(code_hash) => self.apply_upgrade(code_hash)?,
+                Action::Pause => self.apply_paused(true),
+                Action::Terminate(beneficiary) => self.apply_terminate(beneficiary)?,
+                Action::SetAccumulator(addr) => self.apply_set_accumulator(addr)?,
+                Action::SetSubber(addr) => self.apply_set_subber(addr)?,
+                Action::SetOperatorHashes(add_hash, sub_hash) => {
+                    self.apply_set_operator_hashes(add_hash, sub_hash)
+                }
+                Action::MigrateAccumulator(new_acc) => self.apply_migrate_accumulator(new_acc)?,
+                Action::Withdraw(amount, to) => self.apply_withdraw(amount, to)?,
+                Action::SetFee(fee) => self.fee = fee,
+                Action::WithdrawFees(to) => self.apply_withdraw_fees(to)?,
+                Action::SetGasLimit(limit) => self.gas_limit = limit,
+                Action::SetAllowReentry(allow) => self.apply_allow_reentry(allow),
+                Action::GrantRole(account, role) => self.grant(account, role),
+            }
+            Ok(())
+        }
+
+        /// Returns the pending proposal `id`, if any.
+        #[ink(message)]
+        pub fn proposal(&self, id: u32) -> Option<Proposal> {
+            self.proposals.get(id)
+        }
+
+        /// Returns the accounts that may propose and approve admin operations.
+        #[ink(message)]
+        pub fn approvers(&self) -> Vec<AccountId> {
+            self.approvers.clone()
+        }
+
+        /// Returns the number of approvals an admin operation needs, where `0` lets
+        /// the holders of the respective role perform admin operations directly.
+        #[ink(message)]
+        pub fn threshold(&self) -> u32 {
+            self.threshold
+        }
+
+        /// Sets the `adder` smart contract and emits a `TargetUpdated` event unless
+        /// `addr` is not a contract.
+        fn apply_set_adder(&mut self, addr: AccountId) -> Result<()> {
+            ensure_contract(addr)?;
+            let old = self.add_contract();
+            self.add_contract.set(&addr);
+            self.env().emit_event(TargetUpdated {
+                which_target: Target::Adder,
+                old,
+                new: addr,
+            });
+            Ok(())
+        }
+
+        /// Calls the `adder` or `subber`, depending on `which`, to change the
+        /// `accumulator` value by `by` and returns the new `accumulator` value.
+        fn call_operator(
+            &self,
+            which: Which,
+            by: Value,
+            gas_limit: u64,
+            value: Balance,
+            call_flags: CallFlags,
+        ) -> Result<Value> {
+            self.try_call_operator(which, by, gas_limit, value, call_flags)?
+                .map_err(DelegatorError::LangError)
+        }
+
+        /// Like `call_operator`, but returns a `LangError` as the inner result.
+        fn try_call_operator(
+            &self,
+            which: Which,
+            by: Value,
+            gas_limit: u64,
+            value: Balance,
+            call_flags: CallFlags,
+        ) -> Result<core::result::Result<Value, ink::LangError>> {
+            ensure_configured(self.acc_contract())?;
+            match which {
+                Which::Adder => {
+                    let add_contract = self.add_contract();
+                    ensure_configured(add_contract)?;
+                    let mut adder: AdderRef = FromAccountId::from_account_id(add_contract);
+                    let result = adder
+                        .call_mut()
+                        .inc(by)
+                        .gas_limit(gas_limit)
+                        .transferred_value(value)
+                        .call_flags(call_flags)
+                        .try_invoke();
+                    flatten_operator_result(result, which, gas_limit)
+                }
+                Which::Subber => {
+                    let sub_contract = self.sub_contract();
+                    ensure_configured(sub_contract)?;
+                    let mut subber: SubberRef = FromAccountId::from_account_id(sub_contract);
+                    let result = subber
+                        .call_mut()
+                        .dec(by)
+                        .gas_limit(gas_limit)
+                        .transferred_value(value)
+                        .call_flags(call_flags)
+                        .try_invoke();
+                    flatten_operator_result(result, which, gas_limit)
+                }
+            }
+        }
+
+        /// Returns the flags used for calls to other contracts.
+        fn call_flags(&self) -> CallFlags {
+            CallFlags::default().set_allow_reentry(self.allow_reentry)
+        }
+
+        /// Returns `DelegatorError::Paused` if the `delegator` is paused.
+        fn ensure_not_paused(&self) -> Result<()> {
+            if self.paused {
+                return Err(DelegatorError::Paused);
+            }
+            Ok(())
+        }
+
+        /// Returns `DelegatorError::NotAllowed` if the allowlist is enabled and the
+        /// caller is not on it.
+        fn ensure_allowed(&self) -> Result<()> {
+            if self.allowlist_enabled && !self.is_allowed(self.env().caller()) {
+                return Err(DelegatorError::NotAllowed);
+            }
+            Ok(())
+        }
+
+        /// Returns `DelegatorError::DeltaTooLarge` if `max_delta` is set and the
+        /// absolute value of `by` exceeds it.
+        fn ensure_delta_allowed(&self, by: Value) -> Result<()> {
+            if self.max_delta != 0 && by.unsigned_abs() > u128::from(self.max_delta) {
+                return Err(DelegatorError::DeltaTooLarge {
+                    max: self.max_delta,
+                });
+            }
+            Ok(())
+        }
+
+        /// Keeps `fee` for each of the `changes` out of the transferred value and
+        /// returns the rest.
+        ///
+        /// Returns `DelegatorError::InsufficientFee` if less than that was transferred.
+        fn charge_fee(&mut self, changes: u32) -> Result<Balance> {
+            let required = self.fee.saturating_mul(Balance::from(changes));
+            let transferred = self.env().transferred_value();
+            if transferred < required {
+                return Err(DelegatorError::InsufficientFee { required });
+            }
+            self.collected_fees += required;
+            Ok(transferred - required)
+        }
+
+        /// Returns `DelegatorError::Unauthorized` if the caller is not the owner.
+        fn ensure_owner(&self) -> Result<()> {
+            if self.env().caller() != self.owner {
+                return Err(DelegatorError::Unauthorized);
+            }
+            Ok(())
+        }
+
+        /// Returns `DelegatorError::Unauthorized` if the caller does not hold `role`.
+        fn ensure_role(&self, role: Role) -> Result<()> {
+            if !self.has_role(self.env().caller(), role) {
+                return Err(DelegatorError::Unauthorized);
+            }
+            Ok(())
+        }
+
+        /// Returns `DelegatorError::Unauthorized` if `account` is not an approver.
+        fn ensure_approver(&self, account: AccountId) -> Result<()> {
+            if !self.approvers.contains(&account) {
+                return Err(DelegatorError::Unauthorized);
+            }
+            Ok(())
+        }
+
+        /// Returns `DelegatorError::ApprovalRequired` if admin operations need
+        /// approvals.
+        fn ensure_no_threshold(&self) -> Result<()> {
+            if self.threshold > 0 {
+                return Err(DelegatorError::ApprovalRequired);
+            }
+            Ok(())
+        }
+    }
+
+    /// Returns the sentinel address of a contract that has not been set yet.
+    fn unconfigured() -> AccountId {
+        AccountId::from([0u8; 32])
+    }
+
+    /// Returns `DelegatorError::NotAContract` if `addr` is not a contract.
+    ///
+    /// The off-chain environment of the unit tests has no contracts to check
+    /// against, so the check is skipped there.
+    fn ensure_contract(addr: AccountId) -> Result<()> {
+        if cfg!(not(test))
+            && !ink::env::is_contract::<<Delegator as ::ink::env::ContractEnv>::Env>(&addr)
+        {
+            return Err(DelegatorError::NotAContract(addr));
+        }
+        Ok(())
+    }
+
+    /// Flattens the result of a call to the `adder` or `subber` into a `DelegatorError`
+    /// and a `LangError` as the inner result, see `try_change`.
+    ///
+    /// The `result` has three layers: the error of the environment if the call could
+    /// not be made, the `LangError` if the called contract could not dispatch the
+    /// message and the result of the message itself. A trap counts as running out of
+    /// gas if a `gas_limit` was set, whatever actually caused it. Environment errors
+    /// that do not tell the `adder` or `subber` is unreachable, trapped, reverted or
+    /// returned undecodable data are reported as `DelegatorError::EnvFailure`.
+    fn flatten_operator_result<E>(
+        result: core::result::Result<
+            ink::MessageResult<core::result::Result<Value, E>>,
+            ink::env::Error,
+        >,
+        which: Which,
+        gas_limit: u64,
+    ) -> Result<ink::MessageResult<Value>>
+    where
+        DelegatorError: From<E>,
+    {
+        match result {
+            Ok(Ok(Ok(new_value))) => Ok(Ok(new_value)),
+            Ok(Ok(Err(error))) => Err(error.into()),
+            Ok(Err(error)) => Ok(Err(error)),
+            Err(ink::env::Error::CalleeTrapped) if gas_limit > 0 => Err(DelegatorError::OutOfGas),
+            Err(ink::env::Error::NotCallable | ink::env::Error::CodeNotFound) => {
+                Err(DelegatorError::OperatorUnreachable(which))
+            }
+            Err(error) => Err(DelegatorError::from_env(error, DelegatorError::EnvFailure)),
+        }
+    }
+
+    /// Call input that is encoded as the raw bytes, see `raw_call`.
+    ///
+    /// Encoding the bytes as a `Vec<u8>` would prepend their compact encoded length,
+    /// which the called message would try to decode as part of its arguments.
+    struct RawInput<'a>(&'a [u8]);
+
+    impl scale::Encode for RawInput<'_> {
+        fn size_hint(&self) -> usize {
+            self.0.len()
+        }
+
+        fn encode_to<O: scale::Output + ?Sized>(&self, dest: &mut O) {
+            dest.write(self.0);
+        }
+    }
+
+    /// Return data that is decoded as the raw bytes, see `raw_call`.
+    struct RawOutput(Vec<u8>);
+
+    impl scale::Decode for RawOutput {
+        fn decode<I: scale::Input>(input: &mut I) -> core::result::Result<Self, scale::Error> {
+            let len = input
+                .remaining_len()?
+                .ok_or("length of the return data is unknown")?;
+            let mut bytes = ink::prelude::vec![0; len];
+            input.read(&mut bytes)?;
+            Ok(RawOutput(bytes))
+        }
+    }
+
+    /// Returns `DelegatorError::NotConfigured` if `contract` has not been set yet.
+    fn ensure_configured(contract: AccountId) -> Result<()> {
+        if contract == unconfigured() {
+            return Err(DelegatorError::NotConfigured);
+        }
+        Ok(())
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+        use ink::env::{test, DefaultEnvironment};
+
+        #[ink::test]
+        fn new_starts_in_adder_state() {
+            let delegator = new_delegator();
+            assert_eq!(delegator.which(), Which::Adder);
+            assert_eq!(delegator.owner(), accounts().alice);
+            assert_eq!(delegator.version(), (CONTRACT_VERSION, STORAGE_VERSION));
+        }
+
+        #[ink::test]
+        fn is_built_for_a_custom_environment() {
+            assert_eq!(
+                core::any::TypeId::of::<<Delegator as ink::env::ContractEnv>::Env>(),
+                core::any::TypeId::of::<crate::CustomEnvironment>()
+            );
+        }
+
+        #[ink::test]
+        fn migrate_runs_once() {
+            let mut delegator = new_delegator();
+            assert_eq!(delegator.migrate(), Err(DelegatorError::AlreadyMigrated));
+            // Simulates an upgrade from code with an older storage layout.
+            delegator.storage_version = STORAGE_VERSION - 1;
+            assert_eq!(delegator.version(), (CONTRACT_VERSION, STORAGE_VERSION - 1));
+            test::set_caller::<DefaultEnvironment>(accounts().eve);
+            assert_eq!(delegator.migrate(), Err(DelegatorError::Unauthorized));
+            test::set_caller::<DefaultEnvironment>(accounts().alice);
+            assert_eq!(delegator.migrate(), Ok(()));
+            assert_eq!(delegator.version(), (CONTRACT_VERSION, STORAGE_VERSION));
+            assert_eq!(delegator.migrate(), Err(DelegatorError::AlreadyMigrated));
+        }
+
+        #[ink::test]
+        fn new_rejects_invalid_targets() {
+            let accounts = accounts();
+            let hash = Hash::from([1; 32]);
+            let zero = unconfigured();
+            assert_eq!(
+                Delegator::new(
+                    zero,
+                    accounts.charlie,
+                    accounts.django,
+                    hash,
+                    hash,
+                    Settings::default()
+                )
+                .err(),
+                Some(DelegatorError::InvalidTarget)
+            );
+            assert_eq!(
+                Delegator::new(
+                    accounts.bob,
+                    accounts.bob,
+                    accounts.django,
+                    hash,
+                    hash,
+                    Settings::default()
+                )
+                .err(),
+                Some(DelegatorError::DuplicateTarget)
+            );
+        }
+
+        #[ink::test]
+        fn targets_round_trip_through_lazy_storage() {
+            let accounts = accounts();
+            let mut delegator = new_delegator();
+            let targets = delegator.targets();
+            assert_eq!(
+                (targets.acc, targets.add, targets.sub),
+                (accounts.bob, accounts.charlie, accounts.django)
+            );
+            assert_eq!(delegator.set_accumulator(accounts.eve), Ok(()));
+            assert_eq!(delegator.set_subber(accounts.frank), Ok(()));
+            assert_eq!(delegator.accumulator(), accounts.eve);
+            assert_eq!(delegator.adder(), accounts.charlie);
+            assert_eq!(delegator.subber(), accounts.frank);
+        }
+
+        #[ink::test]
+        fn switch_flips_state() {
+            let mut delegator = new_delegator();
+            assert_eq!(delegator.switch(), Ok(()));
+            assert_eq!(delegator.which(), Which::Subber);
+            assert_eq!(delegator.switch(), Ok(()));
+            assert_eq!(delegator.which(), Which::Adder);
+        }
+
+        #[ink::test]
+        fn switch_to_current_state_is_noop() {
+            let mut delegator = new_delegator();
+            assert_eq!(delegator.switch_to(Which::Adder), Ok(()));
+            assert_eq!(delegator.which(), Which::Adder);
+            assert_eq!(delegator.stats().switch_count, 0);
+            assert_eq!(test::recorded_events().count(), 0);
+            assert_eq!(delegator.switch_to(Which::Subber), Ok(()));
+            assert_eq!(delegator.which(), Which::Subber);
+            assert_eq!(test::recorded_events().count(), 1);
+        }
+
+        #[ink::test]
+        fn switched_is_indexed_by_caller() {
+            let accounts = accounts();
+            let mut delegator = new_delegator();
+            assert_eq!(delegator.switch(), Ok(()));
+            let event = test::recorded_events().last().expect("switched");
+            // The event signature, `from`, `to` and `by`.
+            assert_eq!(event.topics.len(), 4);
+            assert!(event
+                .topics
+                .contains(&field_topic(b"Delegator::Switched::by", &accounts.alice)));
+        }
+
+        #[ink::test]
+        fn changed_is_indexed_by_caller_and_operator() {
+            let accounts = accounts();
+            let mut delegator = new_delegator();
+            delegator.record_change(Which::Subber, 1, 1);
+            let event = test::recorded_events().last().expect("changed");
+            // The event signature, `caller` and `via`.
+            assert_eq!(event.topics.len(), 3);
+            assert!(event
+                .topics
+                .contains(&field_topic(b"Delegator::Changed::caller", &accounts.alice)));
+            assert!(event
+                .topics
+                .contains(&field_topic(b"Delegator::Changed::via", &Which::Subber)));
+        }
+
+        #[ink::test]
+        fn fallback_rejects_delta_that_cannot_be_negated() {
+            let mut delegator = Delegator::default();
+            assert_eq!(delegator.set_fallback_enabled(true), Ok(()));
+            assert_eq!(
+                delegator.change(Value::MIN),
+                Err(DelegatorError::DeltaOutOfRange)
+            );
+            assert_eq!(
+                delegator.change(Value::MAX),
+                Err(DelegatorError::NotConfigured)
+            );
+        }
+
+        #[ink::test]
+        fn only_admin_can_raw_call() {
+            let accounts = accounts();
+            let mut delegator = new_delegator();
+            test::set_caller::<DefaultEnvironment>(accounts.eve);
+            assert_eq!(
+                delegator.raw_call(accounts.bob, CHANGE_SELECTOR, Vec::new()),
+                Err(DelegatorError::Unauthorized)
+            );
+        }
+
+        #[ink::test]
+        fn only_admin_can_migrate_accumulator() {
+            let accounts = accounts();
+            let mut delegator = new_delegator();
+            test::set_caller::<DefaultEnvironment>(accounts.eve);
+            assert_eq!(
+                delegator.migrate_accumulator(accounts.frank),
+                Err(DelegatorError::Unauthorized)
+            );
+            assert_eq!(delegator.accumulator(), accounts.bob);
+        }
+
+        /// Decodes the return data of `Adder::inc` the way `try_invoke` does.
+        fn decode_inc(
+            output: &[u8],
+        ) -> core::result::Result<
+            ink::MessageResult<core::result::Result<Value, AdderError>>,
+            ink::env::Error,
+        > {
+            scale::Decode::decode(&mut &output[..]).map_err(ink::env::Error::Decode)
+        }
+
+        #[test]
+        fn operator_result_is_flattened() {
+            let mut output = ink::prelude::vec![0, 0];
+            output.extend_from_slice(&Value::from(5).to_le_bytes());
+            assert_eq!(
+                flatten_operator_result(decode_inc(&output), Which::Adder, 0),
+                Ok(Ok(5))
+            );
+            // The message returned `AdderError::Overflow`.
+            assert_eq!(
+                flatten_operator_result(decode_inc(&[0, 1, 0]), Which::Adder, 0),
+                Err(DelegatorError::AccumulatorRejected(
+                    AccumulatorError::Overflow
+                ))
+            );
+            // The message returned `AdderError::AccumulatorCallFailed`.
+            assert_eq!(
+                flatten_operator_result(decode_inc(&[0, 1, 2]), Which::Adder, 0),
+                Err(DelegatorError::AccumulatorUnreachable)
+            );
+            // The message could not be dispatched.
+            assert_eq!(
+                flatten_operator_result(decode_inc(&[1, 1]), Which::Adder, 0),
+                Ok(Err(ink::LangError::CouldNotReadInput))
+            );
+            // The return data is cut off.
+            assert_eq!(
+                flatten_operator_result(decode_inc(&[0, 0, 5]), Which::Adder, 0),
+                Err(DelegatorError::DecodeFailed)
+            );
+        }
+
+        #[test]
+        fn operator_env_error_is_flattened() {
+            let trapped = || Err(ink::env::Error::CalleeTrapped);
+            assert_eq!(
+                flatten_operator_result::<AdderError>(trapped(), Which::Adder, 0),
+                Err(DelegatorError::TargetReverted)
+            );
+            assert_eq!(
+                flatten_operator_result::<AdderError>(trapped(), Which::Adder, 1_000),
+                Err(DelegatorError::OutOfGas)
+            );
+            assert_eq!(
+                flatten_operator_result::<SubberError>(
+                    Err(ink::env::Error::NotCallable),
+                    Which::Subber,
+                    0
+                ),
+                Err(DelegatorError::OperatorUnreachable(Which::Subber))
+            );
+            assert_eq!(
+                flatten_operator_result::<SubberError>(
+                    Err(ink::env::Error::TransferFailed),
+                    Which::Subber,
+                    0
+                ),
+                Err(DelegatorError::EnvFailure)
+            );
+        }
+
+        #[test]
+        fn raw_input_is_not_length_prefixed() {
+            use scale::Encode;
+            assert_eq!(RawInput(&[1, 2, 3]).encode(), [1, 2, 3]);
+            assert_eq!(
+                <RawOutput as scale::Decode>::decode(&mut &[1, 2, 3][..]).map(|o| o.0),
+                Ok(ink::prelude::vec![1, 2, 3])
+            );
+        }
+
+        #[ink::test]
+        fn fallback_emits_unknown_selector() {
+            let delegator = new_delegator();
+            let before = test::recorded_events().count();
+            assert_eq!(delegator.fallback(), Ok(()));
+            assert_eq!(test::recorded_events().count(), before + 1);
+        }
+
+        #[ink::test]
+        fn reentrant_change_is_rejected() {
+            let mut delegator = Delegator::default();
+            // Simulates a change in progress that a called contract calls back into.
+            delegator.in_call.set(&true);
+            assert_eq!(delegator.change(1), Err(DelegatorError::Reentrancy));
+            assert_eq!(delegator.try_change(1), Err(DelegatorError::Reentrancy));
+            assert_eq!(
+                delegator.broadcast_change(1),
+                Err(DelegatorError::Reentrancy)
+            );
+            assert_eq!(
+                delegator.change_delegate(1),
+                Err(DelegatorError::Reentrancy)
+            );
+            delegator.in_call.set(&false);
+            assert_eq!(delegator.change(1), Err(DelegatorError::NotConfigured));
+            // The guard is released when the change fails.
+            assert_eq!(delegator.change(1), Err(DelegatorError::NotConfigured));
+        }
+
+        #[ink::test]
+        fn only_admin_can_set_max_delta() {
+            let mut delegator = new_delegator();
+            assert_eq!(delegator.max_delta(), 0);
+            let before = test::recorded_events().count();
+            assert_eq!(delegator.set_max_delta(10), Ok(()));
+            assert_eq!(delegator.max_delta(), 10);
+            assert_eq!(test::recorded_events().count(), before + 1);
+            test::set_caller::<DefaultEnvironment>(accounts().eve);
+            assert_eq!(
+                delegator.set_max_delta(0),
+                Err(DelegatorError::Unauthorized)
+            );
+            assert_eq!(delegator.max_delta(), 10);
+        }
+
+        #[ink::test]
+        fn max_delta_bounds_changes() {
+            // The targets are not configured, so deltas that pass the check fail with
+            // `DelegatorError::NotConfigured` instead.
+            let mut delegator = Delegator::default();
+            assert_eq!(
+                delegator.change(Value::MAX),
+                Err(DelegatorError::NotConfigured)
+            );
+            assert_eq!(
+                delegator.change(Value::MIN),
+                Err(DelegatorError::NotConfigured)
+            );
+            delegator.max_delta = 10;
+            let too_large = DelegatorError::DeltaTooLarge { max: 10 };
+            assert_eq!(delegator.change(10), Err(DelegatorError::NotConfigured));
+            assert_eq!(delegator.change(-10), Err(DelegatorError::NotConfigured));
+            assert_eq!(delegator.change(11), Err(too_large));
+            assert_eq!(delegator.change(-11), Err(too_large));
+            // The absolute value of `Value::MIN` does not fit into a `Value`.
+            assert_eq!(delegator.change(Value::MIN), Err(too_large));
+            assert_eq!(delegator.change64(i64::MIN), Err(too_large));
+            assert_eq!(
+                delegator.change_many(ink::prelude::vec![1, Value::MIN]),
+                Err(too_large)
+            );
+            delegator.max_delta = u32::MAX;
+            assert_eq!(
+                delegator.change(Value::from(u32::MAX)),
+                Err(DelegatorError::NotConfigured)
+            );
+            assert_eq!(
+                delegator.change(-Value::from(u32::MAX) - 1),
+                Err(DelegatorError::DeltaTooLarge { max: u32::MAX })
+            );
+        }
+
+        #[ink::test]
+        fn change_with_fallback_rejects_delta_that_cannot_be_negated() {
+            let mut delegator = Delegator::default();
+            assert!(!delegator.fallback_enabled());
+            assert_eq!(
+                delegator.change_with_fallback(Value::MIN),
+                Err(DelegatorError::DeltaOutOfRange)
+            );
+        }
+
+        #[ink::test]
+        fn terminate_is_refused_while_switch_is_pending() {
+            let accounts = accounts();
+            let mut delegator = new_delegator();
+            assert_eq!(delegator.schedule_switch(Which::Subber), Ok(()));
+            assert_eq!(
+                delegator.terminate(accounts.django),
+                Err(DelegatorError::SwitchPending)
+            );
+            test::set_caller::<DefaultEnvironment>(accounts.eve);
+            assert_eq!(
+                delegator.terminate(accounts.eve),
+                Err(DelegatorError::Unauthorized)
+            );
+        }
+
+        #[ink::test]
+        fn withdraw_works() {
+            let accounts = accounts();
+            let mut delegator = new_delegator();
+            let contract = ink::env::account_id::<DefaultEnvironment>();
+            test::set_account_balance::<DefaultEnvironment>(contract, 100);
+            assert_eq!(delegator.balance(), 100);
+            test::set_caller::<DefaultEnvironment>(accounts.eve);
+            assert_eq!(
+                delegator.withdraw(10, accounts.eve),
+                Err(DelegatorError::Unauthorized)
+            );
+            test::set_caller::<DefaultEnvironment>(accounts.alice);
+            assert_eq!(
+                delegator.withdraw(101, accounts.eve),
+                Err(DelegatorError::WithdrawFailed)
+            );
+            assert_eq!(delegator.withdraw(40, accounts.eve), Ok(()));
+            assert_eq!(delegator.balance(), 60);
+        }
+
+        #[ink::test]
+        fn change_requires_fee() {
+            let mut delegator = Delegator::default();
+            delegator.fee = 10;
+            test::set_value_transferred::<DefaultEnvironment>(9);
+            assert_eq!(
+                delegator.change(1),
+                Err(DelegatorError::InsufficientFee { required: 10 })
+            );
+            assert_eq!(delegator.collected_fees(), 0);
+            // The targets are not configured, so a paid change fails afterwards.
+            test::set_value_transferred::<DefaultEnvironment>(15);
+            assert_eq!(delegator.change(1), Err(DelegatorError::NotConfigured));
+        }
+
+        #[ink::test]
+        fn every_change_requires_fee() {
+            let mut delegator = Delegator::default();
+            delegator.fee = 10;
+            test::set_value_transferred::<DefaultEnvironment>(9);
+            let insufficient = DelegatorError::InsufficientFee { required: 10 };
+            assert_eq!(delegator.change_with_fallback(1), Err(insufficient));
+            assert_eq!(delegator.change_with_gas(1, None), Err(insufficient));
+            assert_eq!(delegator.try_change(1), Err(insufficient));
+            assert_eq!(delegator.broadcast_change(1), Err(insufficient));
+            assert_eq!(delegator.change_delegate(1), Err(insufficient));
+            test::set_value_transferred::<DefaultEnvironment>(15);
+            assert_eq!(
+                delegator.change_many(ink::prelude::vec![1, 2]),
+                Err(DelegatorError::InsufficientFee { required: 20 })
+            );
+            assert_eq!(
+                delegator.change_tail(1),
+                Err(DelegatorError::FeeNotSupported)
+            );
+            assert_eq!(delegator.collected_fees(), 0);
+        }
+
+        #[ink::test]
+        fn withdraw_leaves_collected_fees() {
+            let accounts = accounts();
+            let mut delegator = new_delegator();
+            let contract = ink::env::account_id::<DefaultEnvironment>();
+            test::set_account_balance::<DefaultEnvironment>(contract, 100);
+            delegator.collected_fees = 30;
+            assert_eq!(
+                delegator.withdraw(71, accounts.eve),
+                Err(DelegatorError::WithdrawFailed)
+            );
+            assert_eq!(delegator.withdraw(70, accounts.eve), Ok(()));
+            assert_eq!(delegator.balance(), 30);
+            assert_eq!(delegator.collected_fees(), 30);
+        }
+
+        #[ink::test]
+        fn withdraw_fees_works() {
+            let accounts = accounts();
+            let mut delegator = new_delegator();
+            let contract = ink::env::account_id::<DefaultEnvironment>();
+            test::set_account_balance::<DefaultEnvironment>(contract, 100);
+            assert_eq!(delegator.set_fee(10), Ok(()));
+            assert_eq!(delegator.fee(), 10);
+            delegator.collected_fees = 30;
+            test::set_caller::<DefaultEnvironment>(accounts.eve);
+            assert_eq!(delegator.set_fee(0), Err(DelegatorError::Unauthorized));
+            assert_eq!(
+                delegator.withdraw_fees(accounts.eve),
+                Err(DelegatorError::Unauthorized)
+            );
+            test::set_caller::<DefaultEnvironment>(accounts.alice);
+            assert_eq!(delegator.withdraw_fees(accounts.eve), Ok(()));
+            assert_eq!(delegator.collected_fees(), 0);
+            assert_eq!(delegator.balance(), 70);
+        }
+
+        #[ink::test]
+        fn switch_history_evicts_oldest_switch() {
+            let mut delegator = new_delegator();
+            assert_eq!(delegator.switch_history(u32::MAX), Vec::new());
+            for _ in 0..SWITCH_LOG_CAPACITY + 1 {
+                advance_blocks(1);
+                assert_eq!(delegator.switch(), Ok(()));
+            }
+            let history = delegator.switch_history(u32::MAX);
+            assert_eq!(history.len(), SWITCH_LOG_CAPACITY as usize);
+            let newest = history[0];
+            assert_eq!(newest.to, Which::Subber);
+            assert_eq!(newest.by, accounts().alice);
+            // The first switch has been overwritten, so the oldest is the second one.
+            let oldest = history[history.len() - 1];
+            assert_eq!(oldest.to, Which::Adder);
+            assert_eq!(oldest.at, newest.at - SWITCH_LOG_CAPACITY + 1);
+            assert!(history.windows(2).all(|pair| pair[0].at == pair[1].at + 1));
+            assert_eq!(delegator.switch_history(2), history[..2].to_vec());
+        }
+
+        #[ink::test]
+        fn only_admin_can_switch() {
+            let mut delegator = new_delegator();
+            test::set_caller::<DefaultEnvironment>(accounts().eve);
+            assert_eq!(delegator.switch(), Err(DelegatorError::Unauthorized));
+            assert_eq!(delegator.which(), Which::Adder);
+        }
+
+        #[ink::test]
+        fn pause_blocks_switch_and_change() {
+            let mut delegator = new_delegator();
+            assert_eq!(delegator.pause(), Ok(()));
+            assert!(delegator.paused());
+            assert_eq!(delegator.switch(), Err(DelegatorError::Paused));
+            assert_eq!(delegator.change(1), Err(DelegatorError::Paused));
+            assert_eq!(delegator.unpause(), Ok(()));
+            assert!(!delegator.paused());
+            assert_eq!(delegator.switch(), Ok(()));
+        }
+
+        #[ink::test]
+        fn only_pauser_can_pause() {
+            let mut delegator = new_delegator();
+            test::set_caller::<DefaultEnvironment>(accounts().eve);
+            assert_eq!(delegator.pause(), Err(DelegatorError::Unauthorized));
+            assert!(!delegator.paused());
+        }
+
+        #[ink::test]
+        fn pauser_can_pause_but_not_rotate_adder() {
+            let accounts = accounts();
+            let mut delegator = new_delegator();
+            assert_eq!(delegator.grant_role(accounts.bob, Role::Pauser), Ok(()));
+            assert!(delegator.has_role(accounts.bob, Role::Pauser));
+            test::set_caller::<DefaultEnvironment>(accounts.bob);
+            assert_eq!(delegator.pause(), Ok(()));
+            assert!(delegator.paused());
+            assert_eq!(
+                delegator.set_adder(accounts.frank),
+                Err(DelegatorError::Unauthorized)
+            );
+            assert_eq!(delegator.adder(), accounts.charlie);
+        }
+
+        #[ink::test]
+        fn accepted_ownership_moves_all_roles() {
+            let accounts = accounts();
+            let mut delegator = new_delegator();
+            assert_eq!(delegator.propose_owner(accounts.bob), Ok(()));
+            test::set_caller::<DefaultEnvironment>(accounts.bob);
+            assert_eq!(delegator.accept_ownership(), Ok(()));
+            assert_eq!(delegator.owner(), accounts.bob);
+            for role in [Role::Admin, Role::Manager, Role::Pauser] {
+                assert!(!delegator.has_role(accounts.alice, role));
+                assert!(delegator.has_role(accounts.bob, role));
+            }
+            test::set_caller::<DefaultEnvironment>(accounts.alice);
+            assert_eq!(delegator.pause(), Err(DelegatorError::Unauthorized));
+        }
+
+        #[ink::test]
+        fn only_admin_can_grant_roles() {
+            let accounts = accounts();
+            let mut delegator = new_delegator();
+            test::set_caller::<DefaultEnvironment>(accounts.bob);
+            assert_eq!(
+                delegator.grant_role(accounts.bob, Role::Admin),
+                Err(DelegatorError::Unauthorized)
+            );
+            assert!(!delegator.has_role(accounts.bob, Role::Admin));
+        }
+
+        #[ink::test]
+        fn revoked_role_is_rejected() {
+            let accounts = accounts();
+            let mut delegator = new_delegator();
+            assert_eq!(delegator.revoke_role(accounts.alice, Role::Pauser), Ok(()));
+            assert_eq!(delegator.pause(), Err(DelegatorError::Unauthorized));
+        }
+
+        #[ink::test]
+        fn allowlist_rejects_callers_until_allowed() {
+            let accounts = accounts();
+            let mut delegator = Delegator::default();
+            assert_eq!(delegator.set_allowlist_enabled(true), Ok(()));
+            test::set_caller::<DefaultEnvironment>(accounts.bob);
+            assert_eq!(delegator.change(1), Err(DelegatorError::NotAllowed));
+            assert_eq!(delegator.get(), Err(DelegatorError::NotConfigured));
+            test::set_caller::<DefaultEnvironment>(accounts.alice);
+            assert_eq!(delegator.allow(accounts.bob), Ok(()));
+            test::set_caller::<DefaultEnvironment>(accounts.bob);
+            // Passes the allowlist and only fails as no contracts are set off-chain.
+            assert_eq!(delegator.change(1), Err(DelegatorError::NotConfigured));
+        }
+
+        #[ink::test]
+        fn admin_operations_need_approval_with_threshold() {
+            let mut delegator = new_multisig_delegator();
+            assert_eq!(delegator.pause(), Err(DelegatorError::ApprovalRequired));
+            assert_eq!(
+                delegator.set_adder(accounts().frank),
+                Err(DelegatorError::ApprovalRequired)
+            );
+            assert!(!delegator.paused());
+        }
+
+        #[ink::test]
+        fn admin_operations_need_approval_with_threshold() {
+            let accounts = accounts();
+            let mut delegator = new_multisig_delegator();
+            let hash = Hash::from([2; 32]);
+            let approval_required = Err(DelegatorError::ApprovalRequired);
+            assert_eq!(delegator.set_accumulator(accounts.frank), approval_required);
+            assert_eq!(delegator.set_subber(accounts.frank), approval_required);
+            assert_eq!(delegator.set_operator_hashes(hash, hash), approval_required);
+            assert_eq!(
+                delegator.migrate_accumulator(accounts.frank),
+                approval_required
+            );
+            assert_eq!(delegator.withdraw(1, accounts.frank), approval_required);
+            assert_eq!(delegator.set_fee(1), approval_required);
+            assert_eq!(delegator.withdraw_fees(accounts.frank), approval_required);
+            assert_eq!(delegator.set_gas_limit(1), approval_required);
+            assert_eq!(delegator.set_allow_reentry(true), approval_required);
+            assert_eq!(
+                delegator.grant_role(accounts.frank, Role::Admin),
+                approval_required
+            );
+            assert_eq!(
+                delegator.raw_call(accounts.frank, [0; 4], Vec::new()),
+                Err(DelegatorError::ApprovalRequired)
+            );
+            assert_eq!(delegator.fee(), 0);
+            assert_eq!(delegator.targets().sub, accounts.django);
+            assert_eq!(delegator.gas_limit(), 0);
+            assert!(!delegator.allow_reentry());
+            assert!(!delegator.has_role(accounts.frank, Role::Admin));
+        }
+
+        #[ink::test]
+        fn approved_configuration_proposals_are_executed() {
+            let accounts = accounts();
+            let mut delegator = new_multisig_delegator();
+            let actions = [
+                Action::SetGasLimit(1),
+                Action::SetAllowReentry(true),
+                Action::GrantRole(accounts.frank, Role::Admin),
+            ];
+            for action in actions {
+                test::set_caller::<DefaultEnvironment>(accounts.alice);
+                let id = delegator.propose(action).expect("alice is an approver");
+                test::set_caller::<DefaultEnvironment>(accounts.eve);
+                assert_eq!(delegator.approve(id), Ok(()));
+                assert_eq!(delegator.execute(id), Ok(()));
+            }
+            assert_eq!(delegator.gas_limit(), 1);
+            assert!(delegator.allow_reentry());
+            assert!(delegator.has_role(accounts.frank, Role::Admin));
+        }
+
+        #[ink::test]
+        fn approved_fee_proposal_is_executed() {
+            let accounts = accounts();
+            let mut delegator = new_multisig_delegator();
+            let id = delegator
+                .propose(Action::SetFee(5))
+                .expect("alice is an approver");
+            test::set_caller::<DefaultEnvironment>(accounts.eve);
+            assert_eq!(delegator.approve(id), Ok(()));
+            assert_eq!(delegator.execute(id), Ok(()));
+            assert_eq!(delegator.fee(), 5);
+        }
+
+        #[ink::test]
+        fn execute_under_threshold_is_rejected() {
+            let mut delegator = new_multisig_delegator();
+            let id = delegator
+                .propose(Action::Pause)
+                .expect("alice is an approver");
+            assert_eq!(
+                delegator.execute(id),
+                Err(DelegatorError::ThresholdNotReached)
+            );
+            assert!(!delegator.paused());
+        }
+
+        #[ink::test]
+        fn duplicate_approval_is_rejected() {
+            let mut delegator = new_multisig_delegator();
+            let id = delegator
+                .propose(Action::Pause)
+                .expect("alice is an approver");
+            assert_eq!(delegator.approve(id), Err(DelegatorError::AlreadyApproved));
+        }
+
+        #[ink::test]
+        fn approved_proposal_is_executed() {
+            let accounts = accounts();
+            let mut delegator = new_multisig_delegator();
+            let id = delegator
+                .propose(Action::SetAdder(accounts.frank))
+                .expect("alice is an approver");
+            test::set_caller::<DefaultEnvironment>(accounts.bob);
+            assert_eq!(delegator.approve(id), Ok(()));
+            test::set_caller::<DefaultEnvironment>(accounts.frank);
+            assert_eq!(delegator.execute(id), Ok(()));
+            assert_eq!(delegator.adder(), accounts.frank);
+            assert_eq!(delegator.proposal(id), None);
+        }
+
+        #[ink::test]
+        fn last_change_is_none_before_first_change() {
+            let delegator = Delegator::default();
+            assert_eq!(delegator.last_change(), None);
+        }
+
+        #[ink::test]
+        fn stats_count_switches_but_not_failed_changes() {
+            let mut delegator = Delegator::default();
+            assert_eq!(delegator.switch(), Ok(()));
+            assert_eq!(delegator.switch_to(Which::Subber), Ok(()));
+            assert_eq!(delegator.switch_to(Which::Adder), Ok(()));
+            assert_eq!(delegator.change(1), Err(DelegatorError::NotConfigured));
+            assert_eq!(
+                delegator.stats(),
+                Stats {
+                    adder_calls: 0,
+                    subber_calls: 0,
+                    failed_calls: 0,
+                    switch_count: 2,
+                }
+            );
+            assert_eq!(delegator.call_stats(), (0, 0));
+        }
+
+        #[ink::test]
+        fn call_stats_count_successful_changes() {
+            let mut delegator = new_delegator();
+            delegator.record_change(Which::Adder, 1, 1);
+            delegator.record_change(Which::Adder, 1, 2);
+            delegator.record_change(Which::Subber, 1, 1);
+            assert_eq!(delegator.call_stats(), (2, 1));
+        }
+
+        #[ink::test]
+        fn contributions_are_counted_per_caller() {
+            let accounts = accounts();
+            let mut delegator = new_delegator();
+            delegator.record_change(Which::Adder, 5, 5);
+            test::set_caller::<DefaultEnvironment>(accounts.bob);
+            delegator.record_change(Which::Adder, -2, 3);
+            test::set_caller::<DefaultEnvironment>(accounts.alice);
+            delegator.record_change(Which::Adder, -7, -4);
+            // Subtracting `-3` through the `subber` adds `3`.
+            test::set_caller::<DefaultEnvironment>(accounts.bob);
+            delegator.record_change(Which::Subber, -3, -1);
+            assert_eq!(delegator.contribution_of(accounts.alice), -2);
+            assert_eq!(delegator.contribution_of(accounts.bob), 1);
+            assert_eq!(delegator.contribution_of(accounts.eve), 0);
+            assert_eq!(delegator.total_net(), -1);
+            delegator.record_change(Which::Adder, Value::MAX, Value::MAX);
+            delegator.record_change(Which::Adder, Value::MAX, Value::MAX);
+            assert_eq!(delegator.contribution_of(accounts.bob), Value::MAX);
+            assert_eq!(delegator.total_net(), Value::MAX);
+        }
+
+        #[ink::test]
+        fn failed_changes_are_not_contributions() {
+            let mut delegator = Delegator::default();
+            assert_eq!(delegator.change(5), Err(DelegatorError::NotConfigured));
+            assert_eq!(delegator.contribution_of(accounts().alice), 0);
+            assert_eq!(delegator.total_net(), 0);
+        }
+
+        #[ink::test]
+        fn switch_and_change_rolls_back_failed_change() {
+            let mut delegator = new_delegator();
+            delegator.add_contract.set(&unconfigured());
+            delegator.sub_contract.set(&unconfigured());
+            assert_eq!(
+                delegator.switch_and_change(Which::Subber, 1),
+                Err(DelegatorError::NotConfigured)
+            );
+            assert_eq!(delegator.which(), Which::Adder);
+            test::set_caller::<DefaultEnvironment>(accounts().eve);
+            assert_eq!(
+                delegator.switch_and_change(Which::Subber, 1),
+                Err(DelegatorError::Unauthorized)
+            );
+            assert_eq!(delegator.which(), Which::Adder);
+        }
+
+        #[ink::test]
+        fn switch_is_rejected_during_cooldown() {
+            let mut delegator = Delegator::default();
+            assert_eq!(delegator.set_switch_cooldown(2), Ok(()));
+            advance_blocks(2);
+            assert_eq!(delegator.switch(), Ok(()));
+            advance_blocks(1);
+            assert_eq!(
+                delegator.switch(),
+                Err(DelegatorError::CooldownActive { remaining: 1 })
+            );
+            assert_eq!(delegator.which(), Which::Subber);
+        }
+
+        #[ink::test]
+        fn switch_is_allowed_after_cooldown() {
+            let mut delegator = Delegator::default();
+            assert_eq!(delegator.set_switch_cooldown(2), Ok(()));
+            advance_blocks(2);
+            assert_eq!(delegator.switch(), Ok(()));
+            advance_blocks(2);
+            assert_eq!(delegator.switch(), Ok(()));
+            assert_eq!(delegator.which(), Which::Adder);
+        }
+
+        #[ink::test]
+        fn switch_needs_schedule_with_delay() {
+            let mut delegator = Delegator::default();
+            assert_eq!(delegator.set_switch_delay(2), Ok(()));
+            assert_eq!(delegator.switch(), Err(DelegatorError::TimelockRequired));
+            assert_eq!(
+                delegator.execute_switch(),
+                Err(DelegatorError::NoPendingSwitch)
+            );
+        }
+
+        #[ink::test]
+        fn scheduled_switch_is_executed_after_delay() {
+            let mut delegator = Delegator::default();
+            assert_eq!(delegator.set_switch_delay(2), Ok(()));
+            assert_eq!(delegator.schedule_switch(Which::Subber), Ok(()));
+            assert_eq!(delegator.pending_switch(), Some((Which::Subber, 2)));
+            advance_blocks(1);
+            assert_eq!(
+                delegator.execute_switch(),
+                Err(DelegatorError::TimelockNotExpired)
+            );
+            advance_blocks(1);
+            assert_eq!(delegator.execute_switch(), Ok(()));
+            assert_eq!(delegator.which(), Which::Subber);
+            assert_eq!(delegator.pending_switch(), None);
+        }
+
+        #[ink::test]
+        fn rescheduling_switch_restarts_delay() {
+            let mut delegator = Delegator::default();
+            assert_eq!(delegator.set_switch_delay(2), Ok(()));
+            assert_eq!(delegator.schedule_switch(Which::Subber), Ok(()));
+            advance_blocks(1);
+            assert_eq!(delegator.schedule_switch(Which::Subber), Ok(()));
+            advance_blocks(1);
+            assert_eq!(
+                delegator.execute_switch(),
+                Err(DelegatorError::TimelockNotExpired)
+            );
+            assert_eq!(delegator.cancel_switch(), Ok(()));
+            assert_eq!(delegator.pending_switch(), None);
+        }
+
+        #[ink::test]
+        fn recent_changes_is_empty_before_first_change() {
+            let mut delegator = Delegator::default();
+            assert_eq!(delegator.change(1), Err(DelegatorError::NotConfigured));
+            assert_eq!(delegator.recent_changes(), Vec::new());
+        }
+
+        #[ink::test]
+        fn failed_change_does_not_update_last_change() {
+            let mut delegator = Delegator::default();
+            assert_eq!(delegator.change(1), Err(DelegatorError::NotConfigured));
+            assert_eq!(delegator.last_change(), None);
+        }
+
+        fn accounts() -> test::DefaultAccounts<DefaultEnvironment> {
+            test::default_accounts::<DefaultEnvironment>()
+        }
+
+        /// Creates a `delegator` owned by Alice with dummy contracts.
+        fn new_delegator() -> Delegator {
+            let accounts = accounts();
+            test::set_caller::<DefaultEnvironment>(accounts.alice);
+            let hash = Hash::from([1; 32]);
+            Delegator::new(
+                accounts.bob,
+                accounts.charlie,
+                accounts.django,
+                hash,
+                hash,
+                Settings::default(),
+            )
+            .expect("the contracts are valid")
+        }
+
+        /// Creates a `delegator` owned by Alice that needs two approvals of Alice,
+        /// Bob and Eve for admin operations.
+        fn new_multisig_delegator() -> Delegator {
+            let accounts = accounts();
+            test::set_caller::<DefaultEnvironment>(accounts.alice);
+            let hash = Hash::from([1; 32]);
+            Delegator::new(
+                accounts.bob,
+                accounts.charlie,
+                accounts.django,
+                hash,
+                hash,
+                Settings {
+                    approvers: ink::prelude::vec![accounts.alice, accounts.bob, accounts.eve],
+                    threshold: 2,
+                    ..Settings::default()
+                },
+            )
+            .expect("the contracts and approvers are valid")
+        }
+
+        fn advance_blocks(blocks: u32) {
+            for _ in 0..blocks {
+                test::advance_block::<DefaultEnvironment>();
+            }
+        }
+
+        /// Returns the topic ink! emits for an event field with the given `prefix`,
+        /// i.e. `Delegator::<Event>::<field>`, and `value`.
+        ///
+        /// The prefixed value is zero padded to 32 bytes if it fits, and hashed
+        /// otherwise.
+        fn field_topic<T: scale::Encode>(prefix: &[u8], value: &T) -> Vec<u8> {
+            use ink::env::hash::{Blake2x256, CryptoHash};
+            let mut encoded = scale::Encode::encode(&prefix);
+            scale::Encode::encode_to(value, &mut encoded);
+            let mut topic = [0; 32];
+            if encoded.len() <= topic.len() {
+                topic[..encoded.len()].copy_from_slice(&encoded);
+            } else {
+                <Blake2x256 as CryptoHash>::hash(&encoded, &mut topic);
+            }
+            topic.to_vec()
+        }
+    }
+
+    /// This is how you'd write end-to-end (E2E) or integration tests for ink! contracts.
+    ///
+    /// When running these you need to make sure that you:
+    /// - Compile the tests with the `e2e-tests` feature flag enabled (`--features e2e-tests`)
+    /// - Are running a Substrate node which contains `pallet-contracts` in the background
+    #[cfg(all(test, feature = "e2e-tests"))]
+    mod e2e_tests {
+        /// Imports all the definitions from the outer scope so we can use them here.
+        use super::*;
+
+        /// A helper function used for calling contract messages.
+        use ink_e2e::build_message;
+
+        /// The End-to-End test `Result` type.
+        type E2EResult<T> = std::result::Result<T, Box<dyn std::error::Error>>;
+
+        /// The events of the `delegator`.
+        type Event = <Delegator as ink::reflect::ContractEventBase>::Type;
+
+        /// The End-to-End test client.
+        type Client = ink_e2e::Client<ink_e2e::PolkadotConfig, ink::env::DefaultEnvironment>;
+
+        /// Instantiates an `accumulator`, `adder` and `subber` and a `delegator`
+        /// delegating to them, all owned by Alice, and returns the `delegator`.
+        async fn instantiate_delegator(client: &mut Client) -> AccountId {
+            instantiate_delegator_with_min_value(client, Value::MIN).await
+        }
+
+        /// Like `instantiate_delegator`, but the `accumulator` value may not drop
+        /// below `min_value`.
+        async fn instantiate_delegator_with_min_value(
+            client: &mut Client,
+            min_value: Value,
+        ) -> AccountId {
+            let acc_contract = client
+                .instantiate(
+                    "accumulator",
+                    &ink_e2e::alice(),
+                    AccumulatorRef::with_min_value(0, min_value),
+                    0,
+                    None,
+                )
+                .await
+                .expect("instantiate accumulator failed")
+                .account_id;
+            let add_contract = client
+                .instantiate(
+                    "adder",
+                    &ink_e2e::alice(),
+                    AdderRef::new(acc_contract),
+                    0,
+                    None,
+                )
+                .await
+                .expect("instantiate adder failed")
+                .account_id;
+            let sub_contract = client
+                .instantiate(
+                    "subber",
+                    &ink_e2e::alice(),
+                    SubberRef::new(acc_contract),
+                    0,
+                    None,
+                )
+                .await
+                .expect("instantiate subber failed")
+                .account_id;
+            let constructor = DelegatorRef::new(
+                acc_contract,
+                add_contract,
+                sub_contract,
+                Hash::default(),
+                Hash::default(),
+                Settings::default(),
+            );
+            let delegator = client
+                .instantiate("delegator", &ink_e2e::alice(), constructor, 0, None)
+                .await
+                .expect("instantiate delegator failed")
+                .account_id;
+            hand_over_accumulator(client, acc_contract, delegator).await;
+            delegator
+        }
+
+        /// Makes the `delegator` the owner of `accumulator`, which Alice owns, so that
+        /// it can reset it.
+        async fn hand_over_accumulator(
+            client: &mut Client,
+            accumulator: AccountId,
+            delegator: AccountId,
+        ) {
+            let set_owner = build_message::<AccumulatorRef>(accumulator)
+                .call(|accumulator| accumulator.set_owner(delegator));
+            client
+                .call(&ink_e2e::alice(), set_owner, 0, None)
+                .await
+                .expect("set_owner failed")
+                .return_value()
+                .expect("alice owns the accumulator");
+        }
+
+        /// Makes the `delegator` the owner of its `adder` and `subber`, which Alice owns,
+        /// so that it can repoint them to another `accumulator`.
+        async fn hand_over_operators(client: &mut Client, delegator: AccountId) {
+            let targets =
+                build_message::<DelegatorRef>(delegator).call(|delegator| delegator.targets());
+            let targets = client
+                .call_dry_run(&ink_e2e::alice(), &targets, 0, None)
+                .await
+                .return_value();
+            let set_owner =
+                build_message::<AdderRef>(targets.add).call(|adder| adder.set_owner(delegator));
+            client
+                .call(&ink_e2e::alice(), set_owner, 0, None)
+                .await
+                .expect("set_owner failed")
+                .return_value()
+                .expect("alice owns the adder");
+            let set_owner =
+                build_message::<SubberRef>(targets.sub).call(|subber| subber.set_owner(delegator));
+            client
+                .call(&ink_e2e::alice(), set_owner, 0, None)
+                .await
+                .expect("set_owner failed")
+                .return_value()
+                .expect("alice owns the subber");
+        }
+
+        /// Instantiates an `adder` whose `accumulator` is not a contract, so that every
+        /// change routed to it fails.
+        async fn instantiate_broken_adder(client: &mut Client) -> AccountId {
+            let charlie = ink_e2e::account_id(ink_e2e::AccountKeyring::Charlie);
+            client
+                .instantiate("adder", &ink_e2e::alice(), AdderRef::new(charlie), 0, None)
+                .await
+                .expect("instantiate adder failed")
+                .account_id
+        }
+
+        /// Instantiates a `delegator` whose `adder` is a `reentrant` operator calling
+        /// back into `change`, with reentry allowed or not, and returns both.
+        async fn instantiate_reentrant_delegator(
+            client: &mut Client,
+            allow_reentry: bool,
+        ) -> (AccountId, AccountId) {
+            let delegator = instantiate_delegator(client).await;
+            let reentrant = client
+                .instantiate(
+                    "reentrant",
+                    &ink_e2e::alice(),
+                    reentrant::ReentrantRef::new(),
+                    0,
+                    None,
+                )
+                .await
+                .expect("instantiate reentrant failed")
+                .account_id;
+            let set_adder = build_message::<DelegatorRef>(delegator)
+                .call(|delegator| delegator.set_adder(reentrant));
+            client
+                .call(&ink_e2e::alice(), set_adder, 0, None)
+                .await
+                .expect("set_adder failed");
+            let set_allow_reentry = build_message::<DelegatorRef>(delegator)
+                .call(|delegator| delegator.set_allow_reentry(allow_reentry));
+            client
+                .call(&ink_e2e::alice(), set_allow_reentry, 0, None)
+                .await
+                .expect("set_allow_reentry failed");
+            (delegator, reentrant)
+        }
+
+        /// Returns what the `reentrant` operator got back from its last call back into
+        /// the `delegator`.
+        async fn last_result(
+            client: &mut Client,
+            reentrant: AccountId,
+        ) -> Option<core::result::Result<Value, u8>> {
+            let last_result = build_message::<reentrant::ReentrantRef>(reentrant)
+                .call(|reentrant| reentrant.last_result());
+            client
+                .call_dry_run(&ink_e2e::alice(), &last_result, 0, None)
+                .await
+                .return_value()
+        }
+
+        /// Returns the `delegator` events emitted by the call that produced `result`.
+        fn decode_events<V>(
+            result: &ink_e2e::CallResult<ink_e2e::PolkadotConfig, ink::env::DefaultEnvironment, V>,
+        ) -> Vec<Event> {
+            use ink_e2e::events::ContractEmitted;
+            result
+                .events
+                .iter()
+                .filter_map(|event| {
+                    event
+                        .ok()?
+                        .as_event::<ContractEmitted<ink::env::DefaultEnvironment>>()
+                        .ok()
+                        .flatten()
+                })
+                .filter_map(|emitted| <Event as scale::Decode>::decode(&mut &emitted.data[..]).ok())
+                .collect()
+        }
+
+        /// Returns the current `accumulator` value as read by the `delegator`.
+        async fn get(client: &mut Client, delegator: AccountId) -> Result<Value> {
+            let get = build_message::<DelegatorRef>(delegator).call(|delegator| delegator.get());
+            client
+                .call_dry_run(&ink_e2e::alice(), &get, 0, None)
+                .await
+                .return_value()
+        }
+
+        /// We test that we can upload and instantiate the contract using its default constructor.
+        #[ink_e2e::test]
+        async fn default_works(mut client: ink_e2e::Client<C, E>) -> E2EResult<()> {
+            // Given
+            let constructor = DelegatorRef::default();
+
+            // When
+            let contract_account_id = client
+                .instantiate("delegator", &ink_e2e::alice(), constructor, 0, None)
+                .await
+                .expect("instantiate failed")
+                .account_id;
+
+            // Then
+            assert_eq!(
+                get(&mut client, contract_account_id).await,
+                Err(DelegatorError::NotConfigured)
+            );
+
+            Ok(())
+        }
+
+        /// We test that `health_check` reports the contract that does not respond.
+        #[ink_e2e::test(
+            additional_contracts = "accumulator/Cargo.toml adder/Cargo.toml subber/Cargo.toml"
+        )]
+        async fn health_check_works(mut client: ink_e2e::Client<C, E>) -> E2EResult<()> {
+            // Given
+            let delegator = instantiate_delegator(&mut client).await;
+            let health_check =
+                build_message::<DelegatorRef>(delegator).call(|delegator| delegator.health_check());
+            let health = client
+                .call_dry_run(&ink_e2e::alice(), &health_check, 0, None)
+                .await
+                .return_value();
+            assert_eq!(
+                health,
+                Health {
+                    accumulator_ok: true,
+                    adder_ok: true,
+                    subber_ok: true,
+                }
+            );
+
+            // When the `subber` is a contract that is not a `subber`
+            let targets =
+                build_message::<DelegatorRef>(delegator).call(|delegator| delegator.targets());
+            let acc_contract = client
+                .call_dry_run(&ink_e2e::alice(), &targets, 0, None)
+                .await
+                .return_value()
+                .acc;
+            let set_subber = build_message::<DelegatorRef>(delegator)
+                .call(|delegator| delegator.set_subber(acc_contract));
+            client
+                .call(&ink_e2e::alice(), set_subber, 0, None)
+                .await
+                .expect("set_subber failed");
+            let health = client
+                .call_dry_run(&ink_e2e::alice(), &health_check, 0, None)
+                .await
+                .return_value();
+
+            // Then
+            assert_eq!(
+                health,
+                Health {
+                    accumulator_ok: true,
+                    adder_ok: true,
+                    subber_ok: false,
+                }
+            );
+
+            Ok(())
+        }
+
+        /// We test that changes are delegated to the `adder` and, after a switch, to the `subber`.
+        #[ink_e2e::test(
+            additional_contracts = "accumulator/Cargo.toml adder/Cargo.toml subber/Cargo.toml"
+        )]
+        async fn it_works(mut client: ink_e2e::Client<C, E>) -> E2EResult<()> {
+            // Given
+            let delegator = instantiate_delegator(&mut client).await;
+            assert_eq!(get(&mut client, delegator).await, Ok(0));
+
+            // When
+            let change =
+                build_message::<DelegatorRef>(delegator).call(|delegator| delegator.change(5));
+            client
+                .call(&ink_e2e::alice(), change, 0, None)
+                .await
+                .expect("change failed");
+
+            // Then
+            assert_eq!(get(&mut client, delegator).await, Ok(5));
+
+            // When
+            let switch =
+                build_message::<DelegatorRef>(delegator).call(|delegator| delegator.switch());
+            client
+                .call(&ink_e2e::alice(), switch, 0, None)
+                .await
+                .expect("switch failed");
+            let change =
+                build_message::<DelegatorRef>(delegator).call(|delegator| delegator.change(3));
+            client
+                .call(&ink_e2e::alice(), change, 0, None)
+                .await
+                .expect("change failed");
+
+            // Then
+            assert_eq!(get(&mut client, delegator).await, Ok(2));
+            let recent_changes = build_message::<DelegatorRef>(delegator)
+                .call(|delegator| delegator.recent_changes());
+            let recent_changes = client
+                .call_dry_run(&ink_e2e::alice(), &recent_changes, 0, None)
+                .await;
+            assert_eq!(
+                recent_changes.return_value(),
+                ink::prelude::vec![(Which::Adder, 5), (Which::Subber, 3)]
+            );
+
+            Ok(())
+        }
+
+        /// We test that `estimate_change_gas` reverts the change it measures and
+        /// estimates no more than a whole `change` consumes.
+        #[ink_e2e::test(
+            additional_contracts = "accumulator/Cargo.toml adder/Cargo.toml subber/Cargo.toml"
+        )]
+        async fn estimate_change_gas_works(mut client: ink_e2e::Client<C, E>) -> E2EResult<()> {
+            // Given
+            let delegator = instantiate_delegator(&mut client).await;
+            let change =
+                build_message::<DelegatorRef>(delegator).call(|delegator| delegator.change(5));
+            let consumed = client
+                .call_dry_run(&ink_e2e::alice(), &change, 0, None)
+                .await
+                .exec_result
+                .gas_consumed
+                .ref_time();
+
+            // When
+            let estimate_change_gas = build_message::<DelegatorRef>(delegator)
+                .call(|delegator| delegator.estimate_change_gas(5));
+            let estimate = client
+                .call_dry_run(&ink_e2e::alice(), &estimate_change_gas, 0, None)
+                .await
+                .return_value();
+
+            // Then
+            assert!(estimate > 0);
+            assert!(estimate <= consumed);
+            assert_eq!(get(&mut client, delegator).await, Ok(0));
+
+            Ok(())
+        }
+
+        /// We test that `switch` does not pay for loading the targets, which it did
+        /// before they were moved into `Lazy` storage.
+        ///
+        /// The gas of `which`, which loads only the root storage, and of `targets`, which
+        /// loads the targets on top, is compared; their difference is what a `switch`
+        /// saves. The estimates depend on the node and no figures have been recorded
+        /// for it, so only their order is asserted.
+        #[ink_e2e::test(
+            additional_contracts = "accumulator/Cargo.toml adder/Cargo.toml subber/Cargo.toml"
+        )]
+        async fn switch_does_not_load_targets(mut client: ink_e2e::Client<C, E>) -> E2EResult<()> {
+            // Given
+            let delegator = instantiate_delegator(&mut client).await;
+            let which =
+                build_message::<DelegatorRef>(delegator).call(|delegator| delegator.which());
+            let targets =
+                build_message::<DelegatorRef>(delegator).call(|delegator| delegator.targets());
+            let switch =
+                build_message::<DelegatorRef>(delegator).call(|delegator| delegator.switch());
+
+            // When
+            let which_gas = client
+                .call_dry_run(&ink_e2e::alice(), &which, 0, None)
+                .await
+                .exec_result
+                .gas_consumed
+                .ref_time();
+            let targets_gas = client
+                .call_dry_run(&ink_e2e::alice(), &targets, 0, None)
+                .await
+                .exec_result
+                .gas_consumed
+                .ref_time();
+            let switch_gas = client
+                .call_dry_run(&ink_e2e::alice(), &switch, 0, None)
+                .await
+                .exec_result
+                .gas_consumed
+                .ref_time();
+
+            // Then
+            assert!(which_gas < targets_gas);
+            assert!(switch_gas > 0);
+
+            Ok(())
+        }
+
+        /// We test that `switch_and_change` changes the value with the new operator.
+        #[ink_e2e::test(
+            additional_contracts = "accumulator/Cargo.toml adder/Cargo.toml subber/Cargo.toml"
+        )]
+        async fn switch_and_change_works(mut client: ink_e2e::Client<C, E>) -> E2EResult<()> {
+            // Given
+            let delegator = instantiate_delegator(&mut client).await;
+
+            // When
+            let switch_and_change = build_message::<DelegatorRef>(delegator)
+                .call(|delegator| delegator.switch_and_change(Which::Subber, 2));
+            let result = client
+                .call(&ink_e2e::alice(), switch_and_change, 0, None)
+                .await
+                .expect("switch_and_change failed");
+
+            // Then
+            assert_eq!(result.return_value(), Ok(-2));
+            let which =
+                build_message::<DelegatorRef>(delegator).call(|delegator| delegator.which());
+            let which = client
+                .call_dry_run(&ink_e2e::alice(), &which, 0, None)
+                .await
+                .return_value();
+            assert_eq!(which, Which::Subber);
+            assert_eq!(get(&mut client, delegator).await, Ok(-2));
+
+            Ok(())
+        }
+
+        /// We test that a batch is applied as a whole or not at all.
+        #[ink_e2e::test(
+            additional_contracts = "accumulator/Cargo.toml adder/Cargo.toml subber/Cargo.toml"
+        )]
+        async fn change_many_works(mut client: ink_e2e::Client<C, E>) -> E2EResult<()> {
+            // Given
+            let delegator = instantiate_delegator(&mut client).await;
+
+            // When
+            let change_many = build_message::<DelegatorRef>(delegator)
+                .call(|delegator| delegator.change_many(ink::prelude::vec![1, 2, 3]));
+            let result = client
+                .call(&ink_e2e::alice(), change_many, 0, None)
+                .await
+                .expect("change_many failed");
+
+            // Then
+            assert_eq!(result.return_value(), Ok(3));
+            assert_eq!(get(&mut client, delegator).await, Ok(6));
+
+            // When
+            let change_many = build_message::<DelegatorRef>(delegator)
+                .call(|delegator| delegator.change_many(ink::prelude::vec![1, Value::MAX, 1]));
+            let result = client
+                .call_dry_run(&ink_e2e::alice(), &change_many, 0, None)
+                .await;
+
+            // Then
+            assert_eq!(result.return_value(), Err(DelegatorError::BatchFailedAt(1)));
+            assert_eq!(get(&mut client, delegator).await, Ok(6));
+
+            Ok(())
+        }
+
+        /// We test that only allowed callers can change the value with the allowlist enabled.
+        #[ink_e2e::test(
+            additional_contracts = "accumulator/Cargo.toml adder/Cargo.toml subber/Cargo.toml"
+        )]
+        async fn allowlist_works(mut client: ink_e2e::Client<C, E>) -> E2EResult<()> {
+            // Given
+            let delegator = instantiate_delegator(&mut client).await;
+            let enable = build_message::<DelegatorRef>(delegator)
+                .call(|delegator| delegator.set_allowlist_enabled(true));
+            client
+                .call(&ink_e2e::alice(), enable, 0, None)
+                .await
+                .expect("set_allowlist_enabled failed");
+
+            // When
+            let change =
+                build_message::<DelegatorRef>(delegator).call(|delegator| delegator.change(5));
+            let result = client.call_dry_run(&ink_e2e::bob(), &change, 0, None).await;
+
+            // Then
+            assert_eq!(result.return_value(), Err(DelegatorError::NotAllowed));
+
+            // When
+            let bob = ink_e2e::account_id(ink_e2e::AccountKeyring::Bob);
+            let allow =
+                build_message::<DelegatorRef>(delegator).call(|delegator| delegator.allow(bob));
+            client
+                .call(&ink_e2e::alice(), allow, 0, None)
+                .await
+                .expect("allow failed");
+            client
+                .call(&ink_e2e::bob(), change, 0, None)
+                .await
+                .expect("change failed");
+
+            // Then
+            assert_eq!(get(&mut client, delegator).await, Ok(5));
+
+            Ok(())
+        }
+
+        /// We test that broadcasting to an account without a contract emits `DelegationFailed`.
+        #[ink_e2e::test(
+            additional_contracts = "accumulator/Cargo.toml adder/Cargo.toml subber/Cargo.toml"
+        )]
+        async fn broadcast_change_reports_failures(
+            mut client: ink_e2e::Client<C, E>,
+        ) -> E2EResult<()> {
+            // Given
+            let delegator = instantiate_delegator(&mut client).await;
+            let charlie = ink_e2e::account_id(ink_e2e::AccountKeyring::Charlie);
+            let add_accumulator = build_message::<DelegatorRef>(delegator)
+                .call(|delegator| delegator.add_accumulator(charlie));
+            client
+                .call(&ink_e2e::alice(), add_accumulator, 0, None)
+                .await
+                .expect("add_accumulator failed");
+
+            // When
+            let broadcast_change = build_message::<DelegatorRef>(delegator)
+                .call(|delegator| delegator.broadcast_change(1));
+            let result = client
+                .call(&ink_e2e::alice(), broadcast_change, 0, None)
+                .await
+                .expect("broadcast_change failed");
+
+            // Then
+            assert_eq!(result.return_value(), Ok(0));
+            let failures: Vec<_> = decode_events(&result)
+                .into_iter()
+                .filter_map(|event| match event {
+                    Event::DelegationFailed(failure) => Some(failure),
+                    _ => None,
+                })
+                .collect();
+            assert_eq!(failures.len(), 1);
+            assert_eq!(failures[0].target, charlie);
+            assert_eq!(failures[0].reason, FailureReason::EnvError);
+
+            Ok(())
+        }
+
+        /// We test that changes fall back to the other operator if the one in use fails.
+        #[ink_e2e::test(
+            additional_contracts = "accumulator/Cargo.toml adder/Cargo.toml subber/Cargo.toml"
+        )]
+        async fn fallback_works(mut client: ink_e2e::Client<C, E>) -> E2EResult<()> {
+            // Given
+            let delegator = instantiate_delegator(&mut client).await;
+            let enable = build_message::<DelegatorRef>(delegator)
+                .call(|delegator| delegator.set_fallback_enabled(true));
+            client
+                .call(&ink_e2e::alice(), enable, 0, None)
+                .await
+                .expect("set_fallback_enabled failed");
+
+            // When the operator in use works
+            let change =
+                build_message::<DelegatorRef>(delegator).call(|delegator| delegator.change(5));
+            client
+                .call(&ink_e2e::alice(), change, 0, None)
+                .await
+                .expect("change failed");
+
+            // Then
+            assert_eq!(get(&mut client, delegator).await, Ok(5));
+
+            // When the `adder` fails
+            let broken_adder = instantiate_broken_adder(&mut client).await;
+            let set_adder = build_message::<DelegatorRef>(delegator)
+                .call(|delegator| delegator.set_adder(broken_adder));
+            client
+                .call(&ink_e2e::alice(), set_adder, 0, None)
+                .await
+                .expect("set_adder failed");
+            let change =
+                build_message::<DelegatorRef>(delegator).call(|delegator| delegator.change(3));
+            let result = client
+                .call(&ink_e2e::alice(), change, 0, None)
+                .await
+                .expect("change failed");
+
+            // Then the `subber` is used instead
+            assert_eq!(result.return_value(), Ok(8));
+            assert!(decode_events(&result)
+                .into_iter()
+                .any(|event| matches!(event, Event::FellBack(_))));
+
+            // When both operators fail
+            let set_subber = build_message::<DelegatorRef>(delegator)
+                .call(|delegator| delegator.set_subber(broken_adder));
+            client
+                .call(&ink_e2e::alice(), set_subber, 0, None)
+                .await
+                .expect("set_subber failed");
+            let change =
+                build_message::<DelegatorRef>(delegator).call(|delegator| delegator.change(3));
+            let result = client
+                .call_dry_run(&ink_e2e::alice(), &change, 0, None)
+                .await;
+
+            // Then
+            assert_eq!(
+                result.return_value(),
+                Err(DelegatorError::AccumulatorUnreachable)
+            );
+            assert_eq!(get(&mut client, delegator).await, Ok(8));
+
+            Ok(())
+        }
+
+        /// We test that `raw_call` passes the encoded arguments and return value through.
+        #[ink_e2e::test(
+            additional_contracts = "accumulator/Cargo.toml adder/Cargo.toml subber/Cargo.toml"
+        )]
+        async fn raw_call_works(mut client: ink_e2e::Client<C, E>) -> E2EResult<()> {
+            // Given
+            let delegator = instantiate_delegator(&mut client).await;
+            let accumulator =
+                build_message::<DelegatorRef>(delegator).call(|delegator| delegator.accumulator());
+            let acc_contract = client
+                .call_dry_run(&ink_e2e::alice(), &accumulator, 0, None)
+                .await
+                .return_value();
+
+            // When
+            let input = scale::Encode::encode(&Value::from(5));
+            let raw_call = build_message::<DelegatorRef>(delegator)
+                .call(|delegator| delegator.raw_call(acc_contract, CHANGE_SELECTOR, input));
+            let result = client
+                .call(&ink_e2e::alice(), raw_call, 0, None)
+                .await
+                .expect("raw_call failed");
+
+            // Then
+            let output = result.return_value().expect("raw_call returned an error");
+            assert_eq!(
+                <core::result::Result<(), accumulator::AccumulatorError> as scale::Decode>::decode(
+                    &mut &output[..]
+                ),
+                Ok(Ok(()))
+            );
+            assert_eq!(get(&mut client, delegator).await, Ok(5));
+
+            Ok(())
+        }
+
+        /// We test that a call with an unknown selector is handled by `fallback`.
+        #[ink_e2e::test(
+            additional_contracts = "accumulator/Cargo.toml adder/Cargo.toml subber/Cargo.toml"
+        )]
+        async fn unknown_selector_works(mut client: ink_e2e::Client<C, E>) -> E2EResult<()> {
+            // Given
+            let delegator = instantiate_delegator(&mut client).await;
+            let target = client
+                .instantiate(
+                    "delegator",
+                    &ink_e2e::alice(),
+                    DelegatorRef::default(),
+                    0,
+                    None,
+                )
+                .await
+                .expect("instantiate failed")
+                .account_id;
+
+            // When
+            let raw_call = build_message::<DelegatorRef>(delegator)
+                .call(|delegator| delegator.raw_call(target, [0xDE, 0xAD, 0xBE, 0xEF], Vec::new()));
+            let result = client
+                .call(&ink_e2e::alice(), raw_call, 0, None)
+                .await
+                .expect("raw_call failed");
+
+            // Then
+            let output = result.return_value().expect("raw_call returned an error");
+            assert_eq!(
+                <core::result::Result<Result<()>, ink::LangError> as scale::Decode>::decode(
+                    &mut &output[..]
+                ),
+                Ok(Ok(()))
+            );
+            assert!(decode_events(&result).into_iter().any(|event| match event {
+                Event::UnknownSelector(UnknownSelector { caller }) => caller == delegator,
+                _ => false,
+            }));
+
+            Ok(())
+        }
+
+        /// We test that `change64` forwards deltas just inside and just outside of the
+        /// `i32` range unchanged.
+        #[ink_e2e::test(
+            additional_contracts = "accumulator/Cargo.toml adder/Cargo.toml subber/Cargo.toml"
+        )]
+        async fn change64_works(mut client: ink_e2e::Client<C, E>) -> E2EResult<()> {
+            // Given
+            let delegator = instantiate_delegator(&mut client).await;
+
+            // When
+            let change = build_message::<DelegatorRef>(delegator)
+                .call(|delegator| delegator.change64(i64::from(i32::MAX)));
+            let result = client
+                .call(&ink_e2e::alice(), change, 0, None)
+                .await
+                .expect("change64 failed");
+
+            // Then
+            assert_eq!(result.return_value(), Ok(Value::from(i32::MAX)));
+
+            // When
+            let change =
+                build_message::<DelegatorRef>(delegator).call(|delegator| delegator.change64(1));
+            let result = client
+                .call(&ink_e2e::alice(), change, 0, None)
+                .await
+                .expect("change64 failed");
+
+            // Then
+            assert_eq!(result.return_value(), Ok(Value::from(i32::MAX) + 1));
+
+            // When
+            let change = build_message::<DelegatorRef>(delegator)
+                .call(|delegator| delegator.change64(i64::from(i32::MIN) - 1));
+            let result = client
+                .call(&ink_e2e::alice(), change, 0, None)
+                .await
+                .expect("change64 failed");
+
+            // Then
+            assert_eq!(result.return_value(), Ok(-1));
+
+            Ok(())
+        }
+
+        /// We test that `change_with_fallback` reports the operator that made the change.
+        #[ink_e2e::test(
+            additional_contracts = "accumulator/Cargo.toml adder/Cargo.toml subber/Cargo.toml"
+        )]
+        async fn change_with_fallback_works(mut client: ink_e2e::Client<C, E>) -> E2EResult<()> {
+            // Given
+            let delegator = instantiate_delegator(&mut client).await;
+            let change = build_message::<DelegatorRef>(delegator)
+                .call(|delegator| delegator.change_with_fallback(5));
+            let result = client
+                .call(&ink_e2e::alice(), change, 0, None)
+                .await
+                .expect("change_with_fallback failed");
+            assert_eq!(result.return_value(), Ok(Which::Adder));
+
+            // When the `adder` fails
+            let broken_adder = instantiate_broken_adder(&mut client).await;
+            let set_adder = build_message::<DelegatorRef>(delegator)
+                .call(|delegator| delegator.set_adder(broken_adder));
+            client
+                .call(&ink_e2e::alice(), set_adder, 0, None)
+                .await
+                .expect("set_adder failed");
+            let change = build_message::<DelegatorRef>(delegator)
+                .call(|delegator| delegator.change_with_fallback(3));
+            let result = client
+                .call(&ink_e2e::alice(), change, 0, None)
+                .await
+                .expect("change_with_fallback failed");
+
+            // Then the `subber` made the change with the negated delta
+            assert_eq!(result.return_value(), Ok(Which::Subber));
+            assert_eq!(get(&mut client, delegator).await, Ok(8));
+
+            Ok(())
+        }
+
+        /// We test that terminating transfers the balance and removes the `delegator`.
+        #[ink_e2e::test(
+            additional_contracts = "accumulator/Cargo.toml adder/Cargo.toml subber/Cargo.toml"
+        )]
+        async fn terminate_works(mut client: ink_e2e::Client<C, E>) -> E2EResult<()> {
+            // Given
+            let delegator = instantiate_delegator(&mut client).await;
+            let deposit =
+                build_message::<DelegatorRef>(delegator).call(|delegator| delegator.deposit());
+            client
+                .call(&ink_e2e::alice(), deposit, 1_000_000_000, None)
+                .await
+                .expect("deposit failed");
+            let beneficiary = ink_e2e::account_id(ink_e2e::AccountKeyring::Eve);
+            let balance_before = client.balance(beneficiary).await?;
+
+            // When
+            let terminate = build_message::<DelegatorRef>(delegator)
+                .call(|delegator| delegator.terminate(beneficiary));
+            client
+                .call(&ink_e2e::alice(), terminate, 0, None)
+                .await
+                .expect("terminate failed");
+
+            // Then
+            assert!(client.balance(beneficiary).await? > balance_before);
+            let owner =
+                build_message::<DelegatorRef>(delegator).call(|delegator| delegator.owner());
+            assert!(client
+                .call(&ink_e2e::alice(), owner, 0, None)
+                .await
+                .is_err());
+
+            Ok(())
+        }
+
+        /// We test that an admin can withdraw funds deposited into the `delegator`.
+        #[ink_e2e::test(
+            additional_contracts = "accumulator/Cargo.toml adder/Cargo.toml subber/Cargo.toml"
+        )]
+        async fn withdraw_works(mut client: ink_e2e::Client<C, E>) -> E2EResult<()> {
+            // Given
+            let delegator = instantiate_delegator(&mut client).await;
+            let deposit =
+                build_message::<DelegatorRef>(delegator).call(|delegator| delegator.deposit());
+            client
+                .call(&ink_e2e::bob(), deposit, 1_000_000_000, None)
+                .await
+                .expect("deposit failed");
+            let balance =
+                build_message::<DelegatorRef>(delegator).call(|delegator| delegator.balance());
+            let deposited = client
+                .call_dry_run(&ink_e2e::alice(), &balance, 0, None)
+                .await
+                .return_value();
+            let beneficiary = ink_e2e::account_id(ink_e2e::AccountKeyring::Eve);
+            let balance_before = client.balance(beneficiary).await?;
+
+            // When
+            let withdraw = build_message::<DelegatorRef>(delegator)
+                .call(|delegator| delegator.withdraw(400_000_000, beneficiary));
+            client
+                .call(&ink_e2e::alice(), withdraw, 0, None)
+                .await
+                .expect("withdraw failed");
+
+            // Then
+            assert_eq!(
+                client.balance(beneficiary).await?,
+                balance_before + 400_000_000
+            );
+            let remaining = client
+                .call_dry_run(&ink_e2e::alice(), &balance, 0, None)
+                .await
+                .return_value();
+            assert_eq!(remaining, deposited - 400_000_000);
+            let withdraw = build_message::<DelegatorRef>(delegator)
+                .call(|delegator| delegator.withdraw(deposited, beneficiary));
+            let result = client
+                .call_dry_run(&ink_e2e::alice(), &withdraw, 0, None)
+                .await
+                .return_value();
+            assert_eq!(result, Err(DelegatorError::WithdrawFailed));
+
+            Ok(())
+        }
+
+        /// We test that the value transferred along with a change ends up with the
+        /// `accumulator`.
+        #[ink_e2e::test(
+            additional_contracts = "accumulator/Cargo.toml adder/Cargo.toml subber/Cargo.toml"
+        )]
+        async fn transferred_value_is_forwarded(
+            mut client: ink_e2e::Client<C, E>,
+        ) -> E2EResult<()> {
+            // Given
+            let delegator = instantiate_delegator(&mut client).await;
+            let accumulator =
+                build_message::<DelegatorRef>(delegator).call(|delegator| delegator.accumulator());
+            let acc_contract = client
+                .call_dry_run(&ink_e2e::alice(), &accumulator, 0, None)
+                .await
+                .return_value();
+            let balance_before = client.balance(acc_contract).await?;
+
+            // When
+            let change =
+                build_message::<DelegatorRef>(delegator).call(|delegator| delegator.change(5));
+            client
+                .call(&ink_e2e::alice(), change, 1_000_000, None)
+                .await
+                .expect("change failed");
+
+            // Then
+            assert_eq!(
+                client.balance(acc_contract).await?,
+                balance_before + 1_000_000
+            );
+            assert_eq!(get(&mut client, delegator).await, Ok(5));
+
+            Ok(())
+        }
+
+        /// We test that `change` charges the fee and that an admin can withdraw it.
+        #[ink_e2e::test(
+            additional_contracts = "accumulator/Cargo.toml adder/Cargo.toml subber/Cargo.toml"
+        )]
+        async fn fees_work(mut client: ink_e2e::Client<C, E>) -> E2EResult<()> {
+            // Given
+            let fee = 1_000_000;
+            let delegator = instantiate_delegator(&mut client).await;
+            let set_fee =
+                build_message::<DelegatorRef>(delegator).call(|delegator| delegator.set_fee(fee));
+            client
+                .call(&ink_e2e::alice(), set_fee, 0, None)
+                .await
+                .expect("set_fee failed");
+            let balance =
+                build_message::<DelegatorRef>(delegator).call(|delegator| delegator.balance());
+            let balance_before = client
+                .call_dry_run(&ink_e2e::alice(), &balance, 0, None)
+                .await
+                .return_value();
+
+            // When
+            let change =
+                build_message::<DelegatorRef>(delegator).call(|delegator| delegator.change(5));
+            let unpaid = client
+                .call_dry_run(&ink_e2e::bob(), &change, fee - 1, None)
+                .await;
+            client
+                .call(&ink_e2e::bob(), change, fee, None)
+                .await
+                .expect("change failed");
+
+            // Then
+            assert_eq!(
+                unpaid.return_value(),
+                Err(DelegatorError::InsufficientFee { required: fee })
+            );
+            assert_eq!(get(&mut client, delegator).await, Ok(5));
+            let balance_after = client
+                .call_dry_run(&ink_e2e::alice(), &balance, 0, None)
+                .await
+                .return_value();
+            assert_eq!(balance_after, balance_before + fee);
+            let collected_fees = build_message::<DelegatorRef>(delegator)
+                .call(|delegator| delegator.collected_fees());
+            let collected = client
+                .call_dry_run(&ink_e2e::alice(), &collected_fees, 0, None)
+                .await
+                .return_value();
+            assert_eq!(collected, fee);
+
+            // When
+            let beneficiary = ink_e2e::account_id(ink_e2e::AccountKeyring::Eve);
+            let beneficiary_before = client.balance(beneficiary).await?;
+            let withdraw_fees = build_message::<DelegatorRef>(delegator)
+                .call(|delegator| delegator.withdraw_fees(beneficiary));
+            client
+                .call(&ink_e2e::alice(), withdraw_fees, 0, None)
+                .await
+                .expect("withdraw_fees failed");
+
+            // Then
+            assert_eq!(client.balance(beneficiary).await?, beneficiary_before + fee);
+            let collected = client
+                .call_dry_run(&ink_e2e::alice(), &collected_fees, 0, None)
+                .await
+                .return_value();
+            assert_eq!(collected, 0);
+            let balance_after = client
+                .call_dry_run(&ink_e2e::alice(), &balance, 0, None)
+                .await
+                .return_value();
+            assert_eq!(balance_after, balance_before);
+
+            Ok(())
+        }
+
+        /// We test that an overflow rejected by the `accumulator` is surfaced as
+        /// `DelegatorError::AccumulatorRejected`.
+        #[ink_e2e::test(
+            additional_contracts = "accumulator/Cargo.toml adder/Cargo.toml subber/Cargo.toml"
+        )]
+        async fn overflow_is_surfaced(mut client: ink_e2e::Client<C, E>) -> E2EResult<()> {
+            // Given
+            let delegator = instantiate_delegator(&mut client).await;
+            let change = build_message::<DelegatorRef>(delegator)
+                .call(|delegator| delegator.change(Value::MAX));
+            client
+                .call(&ink_e2e::alice(), change, 0, None)
+                .await
+                .expect("change failed");
+
+            // When
+            let change =
+                build_message::<DelegatorRef>(delegator).call(|delegator| delegator.change(1));
+            let result = client
+                .call_dry_run(&ink_e2e::alice(), &change, 0, None)
+                .await;
+
+            // Then
+            assert_eq!(
+                result.return_value(),
+                Err(DelegatorError::AccumulatorRejected(
+                    AccumulatorError::Overflow
+                ))
+            );
+            assert_eq!(get(&mut client, delegator).await, Ok(Value::MAX));
+
+            Ok(())
+        }
+
+        /// We test that a change that would take the `accumulator` value below its
+        /// minimum value is rejected.
+        #[ink_e2e::test(
+            additional_contracts = "accumulator/Cargo.toml adder/Cargo.toml subber/Cargo.toml"
+        )]
+        async fn min_value_works(mut client: ink_e2e::Client<C, E>) -> E2EResult<()> {
+            // Given
+            let delegator = instantiate_delegator_with_min_value(&mut client, 0).await;
+            let change =
+                build_message::<DelegatorRef>(delegator).call(|delegator| delegator.change(2));
+            client
+                .call(&ink_e2e::alice(), change, 0, None)
+                .await
+                .expect("change failed");
+            let switch =
+                build_message::<DelegatorRef>(delegator).call(|delegator| delegator.switch());
+            client
+                .call(&ink_e2e::alice(), switch, 0, None)
+                .await
+                .expect("switch failed");
+
+            // When
+            let change =
+                build_message::<DelegatorRef>(delegator).call(|delegator| delegator.change(3));
+            let result = client
+                .call_dry_run(&ink_e2e::alice(), &change, 0, None)
+                .await;
+
+            // Then
+            assert_eq!(
+                result.return_value(),
+                Err(DelegatorError::AccumulatorRejected(
+                    AccumulatorError::Underflow
+                ))
+            );
+            assert_eq!(get(&mut client, delegator).await, Ok(2));
+
+            Ok(())
+        }
+
+        /// We test that accounts that are not contracts are rejected as targets.
+        #[ink_e2e::test(
+            additional_contracts = "accumulator/Cargo.toml adder/Cargo.toml subber/Cargo.toml"
+        )]
+        async fn targets_must_be_contracts(mut client: ink_e2e::Client<C, E>) -> E2EResult<()> {
+            // Given
+            let alice = ink_e2e::account_id(ink_e2e::AccountKeyring::Alice);
+            let delegator = instantiate_delegator(&mut client).await;
+            let targets =
+                build_message::<DelegatorRef>(delegator).call(|delegator| delegator.targets());
+            let targets = client
+                .call_dry_run(&ink_e2e::alice(), &targets, 0, None)
+                .await
+                .return_value();
+
+            // When
+            let constructor = DelegatorRef::new(
+                alice,
+                targets.add,
+                targets.sub,
+                Hash::default(),
+                Hash::default(),
+                Settings::default(),
+            );
+            let result = client
+                .instantiate("delegator", &ink_e2e::alice(), constructor, 0, None)
+                .await;
+
+            // Then
+            assert!(result.is_err());
+
+            // When
+            let set_accumulator = build_message::<DelegatorRef>(delegator)
+                .call(|delegator| delegator.set_accumulator(alice));
+            let result = client
+                .call_dry_run(&ink_e2e::alice(), &set_accumulator, 0, None)
+                .await;
+
+            // Then
+            assert_eq!(
+                result.return_value(),
+                Err(DelegatorError::NotAContract(alice))
+            );
+
+            Ok(())
+        }
+
+        /// We test that `reset` sets the `accumulator` value back to zero.
+        #[ink_e2e::test(
+            additional_contracts = "accumulator/Cargo.toml adder/Cargo.toml subber/Cargo.toml"
+        )]
+        async fn reset_works(mut client: ink_e2e::Client<C, E>) -> E2EResult<()> {
+            // Given
+            let delegator = instantiate_delegator(&mut client).await;
+            let change =
+                build_message::<DelegatorRef>(delegator).call(|delegator| delegator.change(5));
+            client
+                .call(&ink_e2e::alice(), change, 0, None)
+                .await
+                .expect("change failed");
+
+            // When
+            let reset =
+                build_message::<DelegatorRef>(delegator).call(|delegator| delegator.reset());
+            client
+                .call(&ink_e2e::alice(), reset, 0, None)
+                .await
+                .expect("reset failed");
+
+            // Then
+            assert_eq!(get(&mut client, delegator).await, Ok(0));
+
+            Ok(())
+        }
+
+        /// We test that `migrate_accumulator` carries the value over to the new
+        /// `accumulator` and repoints the `adder` and `subber` to it, and leaves the old
+        /// one in place if the new one rejects it or the operators cannot be repointed.
+        #[ink_e2e::test(
+            additional_contracts = "accumulator/Cargo.toml adder/Cargo.toml subber/Cargo.toml"
+        )]
+        async fn migrate_accumulator_works(mut client: ink_e2e::Client<C, E>) -> E2EResult<()> {
+            // Given
+            let delegator = instantiate_delegator(&mut client).await;
+            let change =
+                build_message::<DelegatorRef>(delegator).call(|delegator| delegator.change(5));
+            client
+                .call(&ink_e2e::alice(), change, 0, None)
+                .await
+                .expect("change failed");
+            let accumulator =
+                build_message::<DelegatorRef>(delegator).call(|delegator| delegator.accumulator());
+            let old_acc = client
+                .call_dry_run(&ink_e2e::alice(), &accumulator, 0, None)
+                .await
+                .return_value();
+            let strict_acc = client
+                .instantiate(
+                    "accumulator",
+                    &ink_e2e::alice(),
+                    AccumulatorRef::with_min_value(0, 10),
+                    0,
+                    None,
+                )
+                .await
+                .expect("instantiate accumulator failed")
+                .account_id;
+            let new_acc = client
+                .instantiate(
+                    "accumulator",
+                    &ink_e2e::alice(),
+                    AccumulatorRef::new(7),
+                    0,
+                    None,
+                )
+                .await
+                .expect("instantiate accumulator failed")
+                .account_id;
+            hand_over_accumulator(&mut client, strict_acc, delegator).await;
+            hand_over_accumulator(&mut client, new_acc, delegator).await;
+
+            // When the new `accumulator` cannot hold the value
+            let migrate = build_message::<DelegatorRef>(delegator)
+                .call(|delegator| delegator.migrate_accumulator(strict_acc));
+            let result = client
+                .call_dry_run(&ink_e2e::alice(), &migrate, 0, None)
+                .await;
+
+            // Then
+            assert_eq!(
+                result.return_value(),
+                Err(DelegatorError::AccumulatorRejected(
+                    AccumulatorError::Underflow
+                ))
+            );
+
+            // When the `delegator` cannot repoint the `adder` and `subber`
+            let migrate = build_message::<DelegatorRef>(delegator)
+                .call(|delegator| delegator.migrate_accumulator(new_acc));
+            let result = client
+                .call_dry_run(&ink_e2e::alice(), &migrate, 0, None)
+                .await;
+
+            // Then
+            assert_eq!(result.return_value(), Err(DelegatorError::Unauthorized));
+
+            // When
+            hand_over_operators(&mut client, delegator).await;
+            let migrate = build_message::<DelegatorRef>(delegator)
+                .call(|delegator| delegator.migrate_accumulator(new_acc));
+            let result = client
+                .call(&ink_e2e::alice(), migrate, 0, None)
+                .await
+                .expect("migrate_accumulator failed");
+
+            // Then
+            assert!(decode_events(&result).into_iter().any(|event| match event {
+                Event::AccumulatorMigrated(AccumulatorMigrated { old, new, value }) => {
+                    (old, new, value) == (old_acc, new_acc, 5)
+                }
+                _ => false,
+            }));
+            let accumulator =
+                build_message::<DelegatorRef>(delegator).call(|delegator| delegator.accumulator());
+            let acc_contract = client
+                .call_dry_run(&ink_e2e::alice(), &accumulator, 0, None)
+                .await
+                .return_value();
+            assert_eq!(acc_contract, new_acc);
+            assert_eq!(get(&mut client, delegator).await, Ok(5));
+            let targets =
+                build_message::<DelegatorRef>(delegator).call(|delegator| delegator.targets());
+            let targets = client
+                .call_dry_run(&ink_e2e::alice(), &targets, 0, None)
+                .await
+                .return_value();
+            let adder_acc =
+                build_message::<AdderRef>(targets.add).call(|adder| adder.accumulator());
+            let adder_acc = client
+                .call_dry_run(&ink_e2e::alice(), &adder_acc, 0, None)
+                .await
+                .return_value();
+            let subber_acc =
+                build_message::<SubberRef>(targets.sub).call(|subber| subber.accumulator());
+            let subber_acc = client
+                .call_dry_run(&ink_e2e::alice(), &subber_acc, 0, None)
+                .await
+                .return_value();
+            assert_eq!((adder_acc, subber_acc), (new_acc, new_acc));
+
+            Ok(())
+        }
+
+        /// We test that an operator calling back into `change` is rejected even if
+        /// reentry is allowed.
+        #[ink_e2e::test(
+            additional_contracts = "accumulator/Cargo.toml adder/Cargo.toml subber/Cargo.toml \
+                                    reentrant/Cargo.toml"
+        )]
+        async fn reentrant_change_is_rejected(mut client: ink_e2e::Client<C, E>) -> E2EResult<()> {
+            // Given
+            let (delegator, reentrant) = instantiate_reentrant_delegator(&mut client, true).await;
+
+            // When
+            let change =
+                build_message::<DelegatorRef>(delegator).call(|delegator| delegator.change(3));
+            client
+                .call(&ink_e2e::alice(), change, 0, None)
+                .await
+                .expect("change failed");
+
+            // Then
+            let reentrancy = scale::Encode::encode(&DelegatorError::Reentrancy)[0];
+            assert_eq!(
+                last_result(&mut client, reentrant).await,
+                Some(Err(reentrancy))
+            );
+            assert_eq!(get(&mut client, delegator).await, Ok(0));
+
+            Ok(())
+        }
+
+        /// We test that an operator calling back into `change` from within `try_change`
+        /// is rejected even if reentry is allowed.
+        #[ink_e2e::test(
+            additional_contracts = "accumulator/Cargo.toml adder/Cargo.toml subber/Cargo.toml \
+                                    reentrant/Cargo.toml"
+        )]
+        async fn reentrant_try_change_is_rejected(
+            mut client: ink_e2e::Client<C, E>,
+        ) -> E2EResult<()> {
+            // Given
+            let (delegator, reentrant) = instantiate_reentrant_delegator(&mut client, true).await;
+
+            // When
+            let try_change =
+                build_message::<DelegatorRef>(delegator).call(|delegator| delegator.try_change(3));
+            client
+                .call(&ink_e2e::alice(), try_change, 0, None)
+                .await
+                .expect("try_change failed");
+
+            // Then
+            let reentrancy = scale::Encode::encode(&DelegatorError::Reentrancy)[0];
+            assert_eq!(
+                last_result(&mut client, reentrant).await,
+                Some(Err(reentrancy))
+            );
+            assert_eq!(get(&mut client, delegator).await, Ok(0));
+
+            Ok(())
+        }
+
+        /// We test that `new_from_hashes` instantiates working contracts, each endowed
+        /// with a quarter of the transferred value.
+        #[ink_e2e::test(
+            additional_contracts = "accumulator/Cargo.toml adder/Cargo.toml subber/Cargo.toml"
+        )]
+        async fn new_from_hashes_works(mut client: ink_e2e::Client<C, E>) -> E2EResult<()> {
+            // Given
+            let acc_hash = client
+                .upload("accumulator", &ink_e2e::alice(), None)
+                .await
+                .expect("upload accumulator failed")
+                .code_hash;
+            let add_hash = client
+                .upload("adder", &ink_e2e::alice(), None)
+                .await
+                .expect("upload adder failed")
+                .code_hash;
+            let sub_hash = client
+                .upload("subber", &ink_e2e::alice(), None)
+                .await
+                .expect("upload subber failed")
+                .code_hash;
+            let endowment = 1_000_000_000;
+
+            // When
+            let constructor = DelegatorRef::new_from_hashes(acc_hash, add_hash, sub_hash, 3, 1);
+            let delegator = client
+                .instantiate(
+                    "delegator",
+                    &ink_e2e::alice(),
+                    constructor,
+                    4 * endowment,
+                    None,
+                )
+                .await
+                .expect("instantiate delegator failed")
+                .account_id;
+
+            // Then
+            let targets =
+                build_message::<DelegatorRef>(delegator).call(|delegator| delegator.targets());
+            let targets = client
+                .call_dry_run(&ink_e2e::alice(), &targets, 0, None)
+                .await
+                .return_value();
+            for target in [targets.acc, targets.add, targets.sub] {
+                assert_eq!(client.balance(target).await?, endowment);
+            }
+            let change =
+                build_message::<DelegatorRef>(delegator).call(|delegator| delegator.change(2));
+            client
+                .call(&ink_e2e::alice(), change, 0, None)
+                .await
+                .expect("change failed");
+            assert_eq!(get(&mut client, delegator).await, Ok(5));
+
+            Ok(())
+        }
+
+        /// We test that a delegated call that runs out of its gas limit is reported as
+        /// `DelegatorError::OutOfGas` and leaves the value unchanged.
+        #[ink_e2e::test(
+            additional_contracts = "accumulator/Cargo.toml adder/Cargo.toml subber/Cargo.toml"
+        )]
+        async fn change_with_gas_runs_out_of_gas(
+            mut client: ink_e2e::Client<C, E>,
+        ) -> E2EResult<()> {
+            // Given
+            let delegator = instantiate_delegator(&mut client).await;
+
+            // When
+            let change_with_gas = build_message::<DelegatorRef>(delegator)
+                .call(|delegator| delegator.change_with_gas(5, Some(1)));
+            let result = client
+                .call_dry_run(&ink_e2e::alice(), &change_with_gas, 0, None)
+                .await;
+
+            // Then
+            assert_eq!(result.return_value(), Err(DelegatorError::OutOfGas));
+            assert_eq!(get(&mut client, delegator).await, Ok(0));
+
+            Ok(())
+        }
+
+        /// We test that `change_tail` still changes the `accumulator` value and that the
+        /// caller receives the return value of the `adder` rather than a `Result<()>`.
+        #[ink_e2e::test(
+            additional_contracts = "accumulator/Cargo.toml adder/Cargo.toml subber/Cargo.toml"
+        )]
+        async fn change_tail_works(mut client: ink_e2e::Client<C, E>) -> E2EResult<()> {
+            // Given
+            let delegator = instantiate_delegator(&mut client).await;
+            let change_tail =
+                build_message::<DelegatorRef>(delegator).call(|delegator| delegator.change_tail(5));
+
+            // When
+            let result = client
+                .call_dry_run(&ink_e2e::alice(), &change_tail, 0, None)
+                .await;
+
+            // Then
+            let output = &result
+                .exec_result
+                .result
+                .as_ref()
+                .expect("change_tail failed")
+                .data;
+            type Output = ink::MessageResult<core::result::Result<Value, AdderError>>;
+            assert_eq!(
+                <Output as scale::Decode>::decode(&mut &output[..]),
+                Ok(Ok(Ok(5)))
+            );
+
+            // When
+            client
+                .call(&ink_e2e::alice(), change_tail, 0, None)
+                .await
+                .expect("change_tail failed");
+
+            // Then
+            assert_eq!(get(&mut client, delegator).await, Ok(5));
+
+            Ok(())
+        }
+
+        /// We test that an operator calling back into the `delegator` only succeeds if
+        /// reentry is allowed.
+        #[ink_e2e::test(
+            additional_contracts = "accumulator/Cargo.toml adder/Cargo.toml subber/Cargo.toml \
+                                    reentrant/Cargo.toml"
+        )]
+        async fn callback_needs_allow_reentry(mut client: ink_e2e::Client<C, E>) -> E2EResult<()> {
+            // Given
+            let (delegator, reentrant) = instantiate_reentrant_delegator(&mut client, false).await;
+
+            // When
+            let change =
+                build_message::<DelegatorRef>(delegator).call(|delegator| delegator.change(3));
+            let result = client
+                .call_dry_run(&ink_e2e::alice(), &change, 0, None)
+                .await;
+
+            // Then
+            assert!(result.return_value().is_err());
+            assert_eq!(last_result(&mut client, reentrant).await, None);
+
+            // When
+            let set_allow_reentry = build_message::<DelegatorRef>(delegator)
+                .call(|delegator| delegator.set_allow_reentry(true));
+            client
+                .call(&ink_e2e::alice(), set_allow_reentry, 0, None)
+                .await
+                .expect("set_allow_reentry failed");
+            let result = client
+                .call_dry_run(&ink_e2e::alice(), &change, 0, None)
+                .await;
+
+            // Then
+            assert_eq!(result.return_value(), Ok(3));
+
+            Ok(())
+        }
+
+        /// We test that `change_delegate` changes the `delegator`'s own value stored under
+        /// `DELEGATED_VALUE_KEY` and leaves the `accumulator` untouched.
+        #[ink_e2e::test(
+            additional_contracts = "accumulator/Cargo.toml adder/Cargo.toml subber/Cargo.toml"
+        )]
+        async fn change_delegate_works(mut client: ink_e2e::Client<C, E>) -> E2EResult<()> {
+            // Given
+            let delegator = instantiate_delegator(&mut client).await;
+            let add_hash = client
+                .upload("adder", &ink_e2e::alice(), None)
+                .await
+                .expect("upload adder failed")
+                .code_hash;
+            let sub_hash = client
+                .upload("subber", &ink_e2e::alice(), None)
+                .await
+                .expect("upload subber failed")
+                .code_hash;
+            let set_hashes = build_message::<DelegatorRef>(delegator)
+                .call(|delegator| delegator.set_operator_hashes(add_hash, sub_hash));
+            client
+                .call(&ink_e2e::alice(), set_hashes, 0, None)
+                .await
+                .expect("set_operator_hashes failed");
+
+            // When
+            let change = build_message::<DelegatorRef>(delegator)
+                .call(|delegator| delegator.change_delegate(5));
+            client
+                .call(&ink_e2e::alice(), change, 0, None)
+                .await
+                .expect("change_delegate failed");
+            let switch =
+                build_message::<DelegatorRef>(delegator).call(|delegator| delegator.switch());
+            client
+                .call(&ink_e2e::alice(), switch, 0, None)
+                .await
+                .expect("switch failed");
+            let change = build_message::<DelegatorRef>(delegator)
+                .call(|delegator| delegator.change_delegate(2));
+            client
+                .call(&ink_e2e::alice(), change, 0, None)
+                .await
+                .expect("change_delegate failed");
+
+            // Then
+            let delegated_value = build_message::<DelegatorRef>(delegator)
+                .call(|delegator| delegator.delegated_value());
+            let delegated_value = client
+                .call_dry_run(&ink_e2e::alice(), &delegated_value, 0, None)
+                .await
+                .return_value();
+            assert_eq!(delegated_value, 3);
+            assert_eq!(get(&mut client, delegator).await, Ok(0));
+
+            Ok(())
+        }
+
+        /// We test that `change_delegate` charges the fee and keeps the transferred value
+        /// through the delegate call.
+        #[ink_e2e::test(
+            additional_contracts = "accumulator/Cargo.toml adder/Cargo.toml subber/Cargo.toml"
+        )]
+        async fn change_delegate_charges_fee(mut client: ink_e2e::Client<C, E>) -> E2EResult<()> {
+            // Given
+            let fee = 1_000_000;
+            let delegator = instantiate_delegator(&mut client).await;
+            let add_hash = client
+                .upload("adder", &ink_e2e::alice(), None)
+                .await
+                .expect("upload adder failed")
+                .code_hash;
+            let set_hashes = build_message::<DelegatorRef>(delegator)
+                .call(|delegator| delegator.set_operator_hashes(add_hash, Hash::default()));
+            client
+                .call(&ink_e2e::alice(), set_hashes, 0, None)
+                .await
+                .expect("set_operator_hashes failed");
+            let set_fee =
+                build_message::<DelegatorRef>(delegator).call(|delegator| delegator.set_fee(fee));
+            client
+                .call(&ink_e2e::alice(), set_fee, 0, None)
+                .await
+                .expect("set_fee failed");
+
+            // When
+            let change = build_message::<DelegatorRef>(delegator)
+                .call(|delegator| delegator.change_delegate(5));
+            let result = client
+                .call(&ink_e2e::bob(), change, fee + 1, None)
+                .await
+                .expect("change_delegate failed");
+
+            // Then
+            assert_eq!(result.return_value(), Ok(()));
+            let delegated_value = build_message::<DelegatorRef>(delegator)
+                .call(|delegator| delegator.delegated_value());
+            let delegated_value = client
+                .call_dry_run(&ink_e2e::alice(), &delegated_value, 0, None)
+                .await
+                .return_value();
+            assert_eq!(delegated_value, 5);
+            let collected_fees = build_message::<DelegatorRef>(delegator)
+                .call(|delegator| delegator.collected_fees());
+            let collected_fees = client
+                .call_dry_run(&ink_e2e::alice(), &collected_fees, 0, None)
+                .await
+                .return_value();
+            assert_eq!(collected_fees, fee);
+
+            Ok(())
+        }
+
+        /// We test that `upgrade` replaces the code of the `delegator` with the code of the
+        /// `delegator_v2` at the same address, emitting `Upgraded` and keeping the storage.
+        #[ink_e2e::test(
+            additional_contracts = "accumulator/Cargo.toml adder/Cargo.toml subber/Cargo.toml \
+                                    delegator_v2/Cargo.toml"
+        )]
+        async fn upgrade_works(mut client: ink_e2e::Client<C, E>) -> E2EResult<()> {
+            // Given
+            let delegator = instantiate_delegator(&mut client).await;
+            let switch =
+                build_message::<DelegatorRef>(delegator).call(|delegator| delegator.switch());
+            client
+                .call(&ink_e2e::alice(), switch, 0, None)
+                .await
+                .expect("switch failed");
+            let targets =
+                build_message::<DelegatorRef>(delegator).call(|delegator| delegator.targets());
+            let targets = client
+                .call_dry_run(&ink_e2e::alice(), &targets, 0, None)
+                .await
+                .return_value();
+            let v2_hash = client
+                .upload("delegator_v2", &ink_e2e::alice(), None)
+                .await
+                .expect("upload delegator_v2 failed")
+                .code_hash;
+
+            // When
+            let upgrade = build_message::<DelegatorRef>(delegator)
+                .call(|delegator| delegator.upgrade(v2_hash));
+            let result = client
+                .call(&ink_e2e::alice(), upgrade, 0, None)
+                .await
+                .expect("upgrade failed");
+
+            // Then
+            let upgraded: Vec<_> = decode_events(&result)
+                .into_iter()
+                .filter_map(|event| match event {
+                    Event::Upgraded(upgraded) => Some(upgraded),
+                    _ => None,
+                })
+                .collect();
+            assert_eq!(upgraded.len(), 1);
+            assert_eq!(upgraded[0].new_hash, v2_hash);
+            assert_ne!(upgraded[0].old_hash, v2_hash);
+            let is_v2 = build_message::<delegator_v2::DelegatorV2Ref>(delegator)
+                .call(|delegator| delegator.is_v2());
+            let is_v2 = client
+                .call_dry_run(&ink_e2e::alice(), &is_v2, 0, None)
+                .await
+                .return_value();
+            assert!(is_v2);
+            let which = build_message::<delegator_v2::DelegatorV2Ref>(delegator)
+                .call(|delegator| delegator.which());
+            let which = client
+                .call_dry_run(&ink_e2e::alice(), &which, 0, None)
+                .await
+                .return_value();
+            assert_eq!(which, delegator_v2::WhichV2::Subber);
+            let acc_contract = build_message::<delegator_v2::DelegatorV2Ref>(delegator)
+                .call(|delegator| delegator.acc_contract());
+            let acc_contract = client
+                .call_dry_run(&ink_e2e::alice(), &acc_contract, 0, None)
+                .await
+                .return_value();
+            assert_eq!(acc_contract, Some(targets.acc));
+
+            Ok(())
+        }
+    }
+}
@@ -0,0 +1,67 @@
+#![cfg_attr(not(feature = "std"), no_std)]
+
+pub use self::reentrant::{Reentrant, ReentrantRef};
+
+/// An operator that calls back into the `delegator` calling it.
+///
+/// Only meant to test that the `delegator` rejects reentrant changes, by setting it
+/// as the `adder` of a `delegator` that allows reentry.
+#[ink::contract]
+pub mod reentrant {
+    use accumulator::Value;
+    use adder::AdderError;
+    use ink::env::{
+        call::{build_call, ExecutionInput, Selector},
+        CallFlags,
+    };
+    use selectors::CHANGE_SELECTOR;
+
+    // `inc` has to be callable like `Adder::inc`.
+    const _: () = assert!(u32::from_be_bytes(CHANGE_SELECTOR) == 0xC0DECAFE);
+
+    /// Calls `change` of its caller from within `inc`.
+    #[ink(storage)]
+    #[derive(Default)]
+    pub struct Reentrant {
+        /// What the last call back into the `delegator` returned.
+        ///
+        /// The error is kept as the index of the `DelegatorError` variant, which is
+        /// all there is to its encoding for variants without fields.
+        last_result: Option<Result<Value, u8>>,
+    }
+
+    impl Reentrant {
+        /// Creates a new `reentrant` operator.
+        #[ink(constructor)]
+        pub fn new() -> Self {
+            Self::default()
+        }
+
+        /// Returns what the last call back into the `delegator` returned, if it could
+        /// be called at all.
+        #[ink(message)]
+        pub fn last_result(&self) -> Option<Result<Value, u8>> {
+            self.last_result
+        }
+
+        /// Calls `change` of the caller with the same amount and pretends to have
+        /// changed the value to `by`.
+        ///
+        /// Returns `AdderError::AccumulatorCallFailed` if the caller could not be called
+        /// back at all, e.g. because it does not allow reentry.
+        #[ink(message, payable, selector = 0xC0DECAFE)]
+        pub fn inc(&mut self, by: Value) -> Result<Value, AdderError> {
+            let result = build_call::<<Self as ::ink::env::ContractEnv>::Env>()
+                .call(self.env().caller())
+                .call_flags(CallFlags::default())
+                .exec_input(
+                    ExecutionInput::new(Selector::new(ink::selector_bytes!("change"))).push_arg(by),
+                )
+                .returns::<Result<Value, u8>>()
+                .try_invoke();
+            let result = result.map_err(|_| AdderError::AccumulatorCallFailed)?;
+            self.last_result = result.ok();
+            Ok(by)
+        }
+    }
+}
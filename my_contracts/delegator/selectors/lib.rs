@@ -0,0 +1,38 @@
+//! Message selectors and storage keys shared by the `accumulator`, `adder`, `subber`
+//! and `delegator` contracts.
+//!
+//! ink! only accepts literals in the `selector` attribute of a message, so every
+//! contract or trait definition declaring one of these selectors asserts at compile
+//! time that its literal matches the constant here. The `accumulator` selectors are
+//! declared by the `Accumulating` trait.
+//!
+//! The contracts call each other through these selectors rather than through the
+//! generated ones, so a mismatch between a message and its selector would only show
+//! at runtime. The contracts therefore also test that each selector dispatches to the
+//! expected message.
+#![no_std]
+
+/// Selector of the messages changing the value: `Accumulator::inc`, `Adder::inc`
+/// and `Subber::dec`.
+///
+/// `Adder::inc` and `Subber::dec` share it so either can be called the same way.
+pub const CHANGE_SELECTOR: [u8; 4] = [0xC0, 0xDE, 0xCA, 0xFE];
+
+/// Selector of `Accumulator::dec`, which `Subber::dec` calls to decrease the value.
+pub const DEC_SELECTOR: [u8; 4] = [0xC0, 0xDE, 0xCA, 0xDD];
+
+/// Selector of `Accumulator::get`.
+pub const GET_SELECTOR: [u8; 4] = [0xC0, 0xDE, 0xCA, 0xF1];
+
+/// Selector of `Accumulator::reset`.
+pub const RESET_SELECTOR: [u8; 4] = [0xC0, 0xDE, 0xCA, 0xF2];
+
+/// Selector of the messages changing the value in delegated mode: `Adder::inc_delegated`
+/// and `Subber::dec_delegated`.
+pub const DELEGATED_CHANGE_SELECTOR: [u8; 4] = [0xC0, 0xDE, 0xCA, 0xFD];
+
+/// Storage key of the value changed in delegated mode.
+///
+/// In delegated mode the `adder` and `subber` code runs against the storage of the
+/// `delegator`, which keeps the value under this key.
+pub const DELEGATED_VALUE_KEY: u32 = 0xC0DE_0001;